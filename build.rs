@@ -0,0 +1,20 @@
+use std::env;
+
+// Forward the libinput version detected by input-sys as cfgs of this crate.
+fn main() {
+    println!("cargo:rerun-if-env-changed=DEP_INPUT_KNOWN_CFGS");
+    println!("cargo:rerun-if-env-changed=DEP_INPUT_CFGS");
+    for cfg in env::var("DEP_INPUT_KNOWN_CFGS")
+        .unwrap_or_default()
+        .split(',')
+    {
+        if !cfg.is_empty() {
+            println!("cargo:rustc-check-cfg=cfg({})", cfg);
+        }
+    }
+    for cfg in env::var("DEP_INPUT_CFGS").unwrap_or_default().split(',') {
+        if !cfg.is_empty() {
+            println!("cargo:rustc-cfg={}", cfg);
+        }
+    }
+}
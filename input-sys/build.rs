@@ -3,7 +3,9 @@ extern crate bindgen;
 
 use std::{env, path::Path};
 
-const LIB_VERSIONS: &[(u8, u8, u8)] = &[
+type Version = (u8, u8, u8);
+
+const LIB_VERSIONS: &[Version] = &[
     (1, 30, 0),
     (1, 29, 0),
     (1, 28, 0),
@@ -18,7 +20,7 @@ const LIB_VERSIONS: &[(u8, u8, u8)] = &[
     (1, 9, 0),
 ];
 
-fn lib_versions() -> impl Iterator<Item = &'static (u8, u8, u8)> {
+fn lib_versions() -> impl Iterator<Item = &'static Version> {
     LIB_VERSIONS
         .iter()
         .filter(|version| {
@@ -31,11 +33,109 @@ fn lib_versions() -> impl Iterator<Item = &'static (u8, u8, u8)> {
         .chain(Some(&LIB_VERSIONS[LIB_VERSIONS.len() - 1]))
 }
 
+/// Query pkg-config for the installed libinput version.
+///
+/// Setting `LIBINPUT_NO_PKG_CONFIG` skips the detection.
+fn installed_version() -> Option<Version> {
+    if env::var_os("LIBINPUT_NO_PKG_CONFIG").is_some() {
+        return None;
+    }
+    let library = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .env_metadata(false)
+        .probe("libinput")
+        .ok()?;
+    let mut parts = library
+        .version
+        .split('.')
+        .map(|part| part.parse::<u8>().ok());
+    Some((
+        parts.next()??,
+        parts.next()??,
+        parts.next().flatten().unwrap_or(0),
+    ))
+}
+
+/// Select the bindings to use, check them against the installed libinput and
+/// export the installed version to dependents.
+fn select_version() -> Version {
+    for var in [
+        "LIBINPUT_STRICT_VERSION",
+        "LIBINPUT_NO_PKG_CONFIG",
+        "PKG_CONFIG",
+        "PKG_CONFIG_PATH",
+        "PKG_CONFIG_LIBDIR",
+        "PKG_CONFIG_SYSROOT_DIR",
+    ] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+    let known_cfgs = LIB_VERSIONS
+        .iter()
+        .map(|version| format!("libinput_detected_{}_{}", version.0, version.1))
+        .collect::<Vec<_>>();
+    for cfg in &known_cfgs {
+        println!("cargo:rustc-check-cfg=cfg({})", cfg);
+    }
+    println!("cargo:known_cfgs={}", known_cfgs.join(","));
+
+    let requested = *lib_versions().next().unwrap();
+    let installed = match installed_version() {
+        Some(installed) => installed,
+        None => return requested,
+    };
+
+    if (requested.0, requested.1) > (installed.0, installed.1) {
+        let message = format!(
+            "The `libinput_{}_{}` feature requires libinput {}.{}, but {}.{}.{} is installed",
+            requested.0,
+            requested.1,
+            requested.0,
+            requested.1,
+            installed.0,
+            installed.1,
+            installed.2
+        );
+        if env::var_os("LIBINPUT_STRICT_VERSION").is_some() {
+            panic!("{}", message);
+        }
+        println!("cargo:warning={}", message);
+    }
+
+    // Every version up to the installed one, e.g. `libinput_detected_1_21`
+    let supported = LIB_VERSIONS
+        .iter()
+        .filter(|version| (version.0, version.1) <= (installed.0, installed.1))
+        .collect::<Vec<_>>();
+    let cfgs = supported
+        .iter()
+        .map(|version| format!("libinput_detected_{}_{}", version.0, version.1))
+        .collect::<Vec<_>>();
+    for cfg in &cfgs {
+        println!("cargo:rustc-cfg={}", cfg);
+    }
+    println!(
+        "cargo:rustc-env=LIBINPUT_DETECTED_VERSION={}.{}.{}",
+        installed.0, installed.1, installed.2
+    );
+    // Available to the build scripts of dependents as `DEP_INPUT_*`
+    println!(
+        "cargo:version={}.{}.{}",
+        installed.0, installed.1, installed.2
+    );
+    println!("cargo:cfgs={}", cfgs.join(","));
+
+    // The newest prebuilt bindings supported by the installed libinput
+    match supported.first() {
+        Some(&&highest) if cfg!(feature = "auto_version") && highest > requested => highest,
+        _ => requested,
+    }
+}
+
 #[cfg(not(feature = "use_bindgen"))]
 fn main() {
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
     let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
-    let version = lib_versions().next().unwrap();
+    let version = select_version();
 
     let bind_name = format!("gen_{}_{}.rs", version.0, version.1);
     let bindings_file = Path::new("src").join("bindings").join(bind_name);
@@ -57,7 +157,7 @@ fn main() {
 
 #[cfg(feature = "use_bindgen")]
 fn main() {
-    let version = lib_versions().next().unwrap();
+    let version = select_version();
     println!(
         "cargo:rustc-env=LIBINPUT_VERSION_STR={}_{}",
         version.0, version.1
@@ -66,7 +166,12 @@ fn main() {
     #[cfg(feature = "update_bindings")]
     let dest_dir = Path::new("src/bindings");
 
-    for version in lib_versions() {
+    let mut versions = lib_versions().copied().collect::<Vec<_>>();
+    if !versions.contains(&version) {
+        versions.push(version);
+    }
+
+    for version in versions {
         let header = Path::new("include").join(format!(
             "libinput.{}.{}.{}.h",
             version.0, version.1, version.2
@@ -0,0 +1,172 @@
+//! Runtime resolution of libinput functions.
+//!
+//! The functions of this module are looked up in the libinput library
+//! loaded at runtime instead of being linked, so they may be used
+//! regardless of the enabled `libinput_1_*` features. Each function
+//! returns `None` if the libinput library in use does not provide it.
+//!
+//! Only the area (libinput 1.27), 3-finger drag (1.28) and eraser button
+//! (1.29) configuration functions are available here. libinput itself is
+//! still linked and all other functions are called directly, so the
+//! `libinput_1_*` features keep selecting the minimum libinput version
+//! required at runtime: a binary built with `libinput_1_21` and `dlopen`
+//! runs on libinput 1.21 and configures areas where libinput supports it,
+//! while one built with `libinput_1_26` still requires libinput 1.26.
+
+use super::{
+    libinput_config_3fg_drag_state, libinput_config_area_rectangle,
+    libinput_config_eraser_button_mode, libinput_config_status, libinput_device,
+    libinput_tablet_tool,
+};
+use std::{
+    ffi::CStr,
+    mem,
+    os::raw::{c_int, c_uint, c_void},
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// Types and constants of libinput versions newer than the enabled
+/// `libinput_1_*` features, required to call the functions of this module.
+///
+/// These are re-exported at the crate root.
+pub mod compat {
+    #[cfg(not(feature = "libinput_1_27"))]
+    #[repr(C)]
+    #[derive(Debug, Copy, Clone)]
+    pub struct libinput_config_area_rectangle {
+        pub x1: f64,
+        pub y1: f64,
+        pub x2: f64,
+        pub y2: f64,
+    }
+
+    #[cfg(not(feature = "libinput_1_28"))]
+    pub type libinput_config_3fg_drag_state = ::std::os::raw::c_uint;
+    #[cfg(not(feature = "libinput_1_28"))]
+    pub const libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED:
+        libinput_config_3fg_drag_state = 0;
+    #[cfg(not(feature = "libinput_1_28"))]
+    pub const libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG:
+        libinput_config_3fg_drag_state = 1;
+    #[cfg(not(feature = "libinput_1_28"))]
+    pub const libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG:
+        libinput_config_3fg_drag_state = 2;
+
+    #[cfg(not(feature = "libinput_1_29"))]
+    pub type libinput_config_eraser_button_mode = ::std::os::raw::c_uint;
+    #[cfg(not(feature = "libinput_1_29"))]
+    pub const libinput_config_eraser_button_mode_LIBINPUT_CONFIG_ERASER_BUTTON_DEFAULT:
+        libinput_config_eraser_button_mode = 0;
+    #[cfg(not(feature = "libinput_1_29"))]
+    pub const libinput_config_eraser_button_mode_LIBINPUT_CONFIG_ERASER_BUTTON_BUTTON:
+        libinput_config_eraser_button_mode = 1;
+}
+
+const LIBRARY_NAME: &CStr = unsafe { CStr::from_bytes_with_nul_unchecked(b"libinput.so.10\0") };
+const UNRESOLVED: *mut c_void = usize::MAX as *mut c_void;
+
+static LIBRARY: AtomicPtr<c_void> = AtomicPtr::new(UNRESOLVED);
+
+fn library() -> *mut c_void {
+    let handle = LIBRARY.load(Ordering::Acquire);
+    if handle != UNRESOLVED {
+        return handle;
+    }
+    let mut handle = unsafe { libc::dlopen(LIBRARY_NAME.as_ptr(), libc::RTLD_LAZY) };
+    if handle.is_null() {
+        // libinput is linked into the process, search the global scope instead
+        handle = libc::RTLD_DEFAULT;
+    }
+    LIBRARY.store(handle, Ordering::Release);
+    handle
+}
+
+fn resolve(cache: &AtomicPtr<c_void>, name: &CStr) -> *mut c_void {
+    let symbol = cache.load(Ordering::Acquire);
+    if symbol != UNRESOLVED {
+        return symbol;
+    }
+    let symbol = unsafe { libc::dlsym(library(), name.as_ptr()) };
+    cache.store(symbol, Ordering::Release);
+    symbol
+}
+
+/// Check if the libinput library in use provides a function of the given name.
+pub fn has_symbol(name: &CStr) -> bool {
+    !unsafe { libc::dlsym(library(), name.as_ptr()) }.is_null()
+}
+
+macro_rules! dynamic_fn {
+    ($(pub fn $name:ident($($arg:ident: $arg_ty:ty),*) -> $ret:ty;)*) => {
+        $(
+            #[doc = concat!("Call `", stringify!($name), "` if available.")]
+            ///
+            /// # Safety
+            ///
+            /// The same requirements as for calling the linked function apply.
+            pub unsafe fn $name($($arg: $arg_ty),*) -> Option<$ret> {
+                static SYMBOL: AtomicPtr<c_void> = AtomicPtr::new(UNRESOLVED);
+                let symbol = resolve(
+                    &SYMBOL,
+                    CStr::from_bytes_with_nul_unchecked(concat!(stringify!($name), "\0").as_bytes()),
+                );
+                if symbol.is_null() {
+                    None
+                } else {
+                    let func: unsafe extern "C" fn($($arg_ty),*) -> $ret = mem::transmute(symbol);
+                    Some(func($($arg),*))
+                }
+            }
+        )*
+    };
+}
+
+dynamic_fn! {
+    pub fn libinput_device_config_area_has_rectangle(device: *mut libinput_device) -> c_int;
+    pub fn libinput_device_config_area_set_rectangle(
+        device: *mut libinput_device,
+        rect: *const libinput_config_area_rectangle
+    ) -> libinput_config_status;
+    pub fn libinput_device_config_area_get_rectangle(
+        device: *mut libinput_device
+    ) -> libinput_config_area_rectangle;
+    pub fn libinput_device_config_area_get_default_rectangle(
+        device: *mut libinput_device
+    ) -> libinput_config_area_rectangle;
+
+    pub fn libinput_device_config_3fg_drag_get_finger_count(device: *mut libinput_device) -> c_int;
+    pub fn libinput_device_config_3fg_drag_set_enabled(
+        device: *mut libinput_device,
+        enable: libinput_config_3fg_drag_state
+    ) -> libinput_config_status;
+    pub fn libinput_device_config_3fg_drag_get_enabled(
+        device: *mut libinput_device
+    ) -> libinput_config_3fg_drag_state;
+    pub fn libinput_device_config_3fg_drag_get_default_enabled(
+        device: *mut libinput_device
+    ) -> libinput_config_3fg_drag_state;
+
+    pub fn libinput_tablet_tool_config_eraser_button_get_modes(
+        tool: *mut libinput_tablet_tool
+    ) -> u32;
+    pub fn libinput_tablet_tool_config_eraser_button_set_mode(
+        tool: *mut libinput_tablet_tool,
+        mode: libinput_config_eraser_button_mode
+    ) -> libinput_config_status;
+    pub fn libinput_tablet_tool_config_eraser_button_get_mode(
+        tool: *mut libinput_tablet_tool
+    ) -> libinput_config_eraser_button_mode;
+    pub fn libinput_tablet_tool_config_eraser_button_get_default_mode(
+        tool: *mut libinput_tablet_tool
+    ) -> libinput_config_eraser_button_mode;
+    pub fn libinput_tablet_tool_config_eraser_button_set_button(
+        tool: *mut libinput_tablet_tool,
+        button: u32
+    ) -> libinput_config_status;
+    pub fn libinput_tablet_tool_config_eraser_button_get_button(
+        tool: *mut libinput_tablet_tool
+    ) -> c_uint;
+    pub fn libinput_tablet_tool_config_eraser_button_get_default_button(
+        tool: *mut libinput_tablet_tool
+    ) -> c_uint;
+}
@@ -15,5 +15,16 @@ include!(concat!(
     ".rs"
 ));
 
+/// The libinput version found by pkg-config at build time, if any.
+pub const DETECTED_VERSION: Option<&str> = option_env!("LIBINPUT_DETECTED_VERSION");
+
+#[cfg(feature = "dlopen")]
+pub mod dynamic;
+#[cfg(feature = "dlopen")]
+#[allow(unused_imports)]
+pub use dynamic::compat::*;
+
+// Also linked with the `dlopen` feature, which only resolves the functions of
+// `dynamic` at runtime
 #[link(name = "input")]
 extern "C" {}
@@ -61,7 +61,7 @@ impl AccelConfig {
         step: f64,
         points: &[f64],
     ) -> DeviceConfigResult {
-        match unsafe {
+        DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_config_accel_set_points(
                 self.0,
                 match accel_type {
@@ -75,16 +75,7 @@ impl AccelConfig {
                 points.len(),
                 points.as_ptr() as _,
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        })
     }
 }
 
@@ -0,0 +1,598 @@
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+use crate::AreaRectangle;
+#[cfg(feature = "libinput_1_26")]
+use crate::ClickfingerButtonMap;
+#[cfg(feature = "rules")]
+use crate::DeviceRules;
+#[cfg(feature = "libinput_1_15")]
+use crate::ScrollButtonLockState;
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+use crate::ThreeFingerDragState;
+use crate::{
+    AccelProfile, ClickMethod, Device, DeviceConfigError, DeviceConfigResult, DeviceKey,
+    DragLockState, Libinput, ScrollMethod, SendEventsMode, TapButtonMap,
+};
+#[cfg(feature = "rules")]
+use std::rc::Rc;
+use std::{cell::RefCell, collections::HashMap, error::Error as StdError, fmt, mem};
+
+/// A configuration setting of a device, as applied by one of the
+/// `Device::config_*` setters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum DeviceSetting {
+    /// See `Device::config_accel_set_profile`
+    AccelProfile(AccelProfile),
+    /// See `Device::config_accel_set_speed`
+    AccelSpeed(f64),
+    /// See `Device::config_area_set_rectangle`
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+    Area(AreaRectangle),
+    /// See `Device::config_calibration_set_matrix`
+    CalibrationMatrix([f32; 6]),
+    /// See `Device::config_click_set_method`
+    ClickMethod(ClickMethod),
+    /// See `Device::config_click_clickfinger_set_button_map`
+    #[cfg(feature = "libinput_1_26")]
+    ClickfingerButtonMap(ClickfingerButtonMap),
+    /// See `Device::config_dwt_set_enabled`
+    Dwt(bool),
+    /// See `Device::config_dwtp_set_enabled`
+    #[cfg(feature = "libinput_1_21")]
+    Dwtp(bool),
+    /// See `Device::config_left_handed_set`
+    LeftHanded(bool),
+    /// See `Device::config_middle_emulation_set_enabled`
+    MiddleEmulation(bool),
+    /// See `Device::config_rotation_set_angle`
+    RotationAngle(u32),
+    /// See `Device::config_scroll_set_method`
+    ScrollMethod(ScrollMethod),
+    /// See `Device::config_scroll_set_natural_scroll_enabled`
+    NaturalScroll(bool),
+    /// See `Device::config_scroll_set_button`
+    ScrollButton(u32),
+    /// See `Device::config_scroll_set_button_lock`
+    #[cfg(feature = "libinput_1_15")]
+    ScrollButtonLock(ScrollButtonLockState),
+    /// See `Device::config_send_events_set_mode`
+    SendEventsMode(SendEventsMode),
+    /// See `Device::config_3fg_drag_set_enabled`
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+    ThreeFingerDrag(ThreeFingerDragState),
+    /// See `Device::config_tap_set_button_map`
+    TapButtonMap(TapButtonMap),
+    /// See `Device::config_tap_set_drag_enabled`
+    TapDrag(bool),
+    /// See `Device::config_tap_set_drag_lock_enabled`
+    TapDragLock(DragLockState),
+    /// See `Device::config_tap_set_enabled`
+    Tap(bool),
+}
+
+impl DeviceSetting {
+    /// Apply this setting to a device with the matching setter.
+    pub fn apply(&self, device: &Device) -> DeviceConfigResult {
+        let mut device = device.clone();
+        match *self {
+            DeviceSetting::AccelProfile(profile) => device.config_accel_set_profile(profile),
+            DeviceSetting::AccelSpeed(speed) => device.config_accel_set_speed(speed),
+            #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+            DeviceSetting::Area(area) => device.config_area_set_rectangle(area),
+            DeviceSetting::CalibrationMatrix(matrix) => {
+                device.config_calibration_set_matrix(matrix)
+            }
+            DeviceSetting::ClickMethod(method) => device.config_click_set_method(method),
+            #[cfg(feature = "libinput_1_26")]
+            DeviceSetting::ClickfingerButtonMap(map) => {
+                device.config_click_clickfinger_set_button_map(map)
+            }
+            DeviceSetting::Dwt(enabled) => device.config_dwt_set_enabled(enabled),
+            #[cfg(feature = "libinput_1_21")]
+            DeviceSetting::Dwtp(enabled) => device.config_dwtp_set_enabled(enabled),
+            DeviceSetting::LeftHanded(enabled) => device.config_left_handed_set(enabled),
+            DeviceSetting::MiddleEmulation(enabled) => {
+                device.config_middle_emulation_set_enabled(enabled)
+            }
+            DeviceSetting::RotationAngle(angle) => device.config_rotation_set_angle(angle),
+            DeviceSetting::ScrollMethod(method) => device.config_scroll_set_method(method),
+            DeviceSetting::NaturalScroll(enabled) => {
+                device.config_scroll_set_natural_scroll_enabled(enabled)
+            }
+            DeviceSetting::ScrollButton(button) => device.config_scroll_set_button(button),
+            #[cfg(feature = "libinput_1_15")]
+            DeviceSetting::ScrollButtonLock(state) => device.config_scroll_set_button_lock(state),
+            DeviceSetting::SendEventsMode(mode) => device.config_send_events_set_mode(mode),
+            #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+            DeviceSetting::ThreeFingerDrag(state) => device.config_3fg_drag_set_enabled(state),
+            DeviceSetting::TapButtonMap(map) => device.config_tap_set_button_map(map),
+            DeviceSetting::TapDrag(enabled) => device.config_tap_set_drag_enabled(enabled),
+            DeviceSetting::TapDragLock(state) => device.config_tap_set_drag_lock_enabled(state),
+            DeviceSetting::Tap(enabled) => device.config_tap_set_enabled(enabled),
+        }
+    }
+
+    /// Read the current value of this setting from a device.
+    ///
+    /// Returns `None` if the device does not support the setting.
+    pub fn current(&self, device: &Device) -> Option<DeviceSetting> {
+        let tapping = device.config_tap_finger_count() > 0;
+        Some(match *self {
+            DeviceSetting::AccelProfile(_) => {
+                DeviceSetting::AccelProfile(device.config_accel_profile()?)
+            }
+            DeviceSetting::AccelSpeed(_) if device.config_accel_is_available() => {
+                DeviceSetting::AccelSpeed(device.config_accel_speed())
+            }
+            #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+            DeviceSetting::Area(_) if device.config_area_has_rectangle() => {
+                DeviceSetting::Area(device.config_area_get_rectangle().ok()?)
+            }
+            DeviceSetting::CalibrationMatrix(_) if device.config_calibration_has_matrix() => {
+                DeviceSetting::CalibrationMatrix(device.config_calibration_matrix()?)
+            }
+            DeviceSetting::ClickMethod(_) => {
+                DeviceSetting::ClickMethod(device.config_click_method()?)
+            }
+            #[cfg(feature = "libinput_1_26")]
+            DeviceSetting::ClickfingerButtonMap(_)
+                if device
+                    .config_click_methods()
+                    .contains(&ClickMethod::Clickfinger) =>
+            {
+                DeviceSetting::ClickfingerButtonMap(device.config_click_clickfinger_button_map())
+            }
+            DeviceSetting::Dwt(_) if device.config_dwt_is_available() => {
+                DeviceSetting::Dwt(device.config_dwt_enabled().ok()?)
+            }
+            #[cfg(feature = "libinput_1_21")]
+            DeviceSetting::Dwtp(_) if device.config_dwtp_is_available() => {
+                DeviceSetting::Dwtp(device.config_dwtp_enabled().ok()?)
+            }
+            DeviceSetting::LeftHanded(_) if device.config_left_handed_is_available() => {
+                DeviceSetting::LeftHanded(device.config_left_handed())
+            }
+            DeviceSetting::MiddleEmulation(_) if device.config_middle_emulation_is_available() => {
+                DeviceSetting::MiddleEmulation(device.config_middle_emulation_enabled().ok()?)
+            }
+            DeviceSetting::RotationAngle(_) if device.config_rotation_is_available() => {
+                DeviceSetting::RotationAngle(device.config_rotation_angle())
+            }
+            DeviceSetting::ScrollMethod(_) if !device.config_scroll_methods().is_empty() => {
+                DeviceSetting::ScrollMethod(device.config_scroll_method()?)
+            }
+            DeviceSetting::NaturalScroll(_) if device.config_scroll_has_natural_scroll() => {
+                DeviceSetting::NaturalScroll(device.config_scroll_natural_scroll_enabled())
+            }
+            DeviceSetting::ScrollButton(_)
+                if device
+                    .config_scroll_methods()
+                    .contains(&ScrollMethod::OnButtonDown) =>
+            {
+                DeviceSetting::ScrollButton(device.config_scroll_button())
+            }
+            #[cfg(feature = "libinput_1_15")]
+            DeviceSetting::ScrollButtonLock(_)
+                if device
+                    .config_scroll_methods()
+                    .contains(&ScrollMethod::OnButtonDown) =>
+            {
+                DeviceSetting::ScrollButtonLock(device.config_scroll_button_lock())
+            }
+            DeviceSetting::SendEventsMode(_) => {
+                DeviceSetting::SendEventsMode(device.config_send_events_mode())
+            }
+            #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+            DeviceSetting::ThreeFingerDrag(_)
+                if device
+                    .config_3fg_drag_get_finger_count()
+                    .map_or(false, |count| count >= 3) =>
+            {
+                DeviceSetting::ThreeFingerDrag(device.config_3fg_drag_get_enabled().ok()?)
+            }
+            DeviceSetting::TapButtonMap(_) if tapping => {
+                DeviceSetting::TapButtonMap(device.config_tap_button_map()?)
+            }
+            DeviceSetting::TapDrag(_) if tapping => {
+                DeviceSetting::TapDrag(device.config_tap_drag_enabled().ok()?)
+            }
+            DeviceSetting::TapDragLock(_) if tapping => {
+                DeviceSetting::TapDragLock(device.config_tap_drag_lock_state())
+            }
+            DeviceSetting::Tap(_) if tapping => {
+                DeviceSetting::Tap(device.config_tap_enabled().ok()?)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Read the default value of this setting from a device.
+    ///
+    /// Returns `None` if the device does not support the setting.
+    pub fn device_default(&self, device: &Device) -> Option<DeviceSetting> {
+        let tapping = device.config_tap_finger_count() > 0;
+        Some(match *self {
+            DeviceSetting::AccelProfile(_) => {
+                DeviceSetting::AccelProfile(device.config_accel_default_profile()?)
+            }
+            DeviceSetting::AccelSpeed(_) if device.config_accel_is_available() => {
+                DeviceSetting::AccelSpeed(device.config_accel_default_speed())
+            }
+            #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+            DeviceSetting::Area(_) if device.config_area_has_rectangle() => {
+                DeviceSetting::Area(device.config_area_get_default_rectangle().ok()?)
+            }
+            DeviceSetting::CalibrationMatrix(_) if device.config_calibration_has_matrix() => {
+                DeviceSetting::CalibrationMatrix(device.config_calibration_default_matrix()?)
+            }
+            DeviceSetting::ClickMethod(_) => {
+                DeviceSetting::ClickMethod(device.config_click_default_method()?)
+            }
+            #[cfg(feature = "libinput_1_26")]
+            DeviceSetting::ClickfingerButtonMap(_)
+                if device
+                    .config_click_methods()
+                    .contains(&ClickMethod::Clickfinger) =>
+            {
+                DeviceSetting::ClickfingerButtonMap(
+                    device.config_click_clickfinger_default_button_map(),
+                )
+            }
+            DeviceSetting::Dwt(_) if device.config_dwt_is_available() => {
+                DeviceSetting::Dwt(device.config_dwt_default_enabled().ok()?)
+            }
+            #[cfg(feature = "libinput_1_21")]
+            DeviceSetting::Dwtp(_) if device.config_dwtp_is_available() => {
+                DeviceSetting::Dwtp(device.config_dwtp_default_enabled().ok()?)
+            }
+            DeviceSetting::LeftHanded(_) if device.config_left_handed_is_available() => {
+                DeviceSetting::LeftHanded(device.config_left_handed_default())
+            }
+            DeviceSetting::MiddleEmulation(_) if device.config_middle_emulation_is_available() => {
+                DeviceSetting::MiddleEmulation(
+                    device.config_middle_emulation_default_enabled().ok()?,
+                )
+            }
+            DeviceSetting::RotationAngle(_) if device.config_rotation_is_available() => {
+                DeviceSetting::RotationAngle(device.config_rotation_default_angle())
+            }
+            DeviceSetting::ScrollMethod(_) if !device.config_scroll_methods().is_empty() => {
+                DeviceSetting::ScrollMethod(device.config_scroll_default_method()?)
+            }
+            DeviceSetting::NaturalScroll(_) if device.config_scroll_has_natural_scroll() => {
+                DeviceSetting::NaturalScroll(device.config_scroll_default_natural_scroll_enabled())
+            }
+            DeviceSetting::ScrollButton(_)
+                if device
+                    .config_scroll_methods()
+                    .contains(&ScrollMethod::OnButtonDown) =>
+            {
+                DeviceSetting::ScrollButton(device.config_scroll_default_button())
+            }
+            #[cfg(feature = "libinput_1_15")]
+            DeviceSetting::ScrollButtonLock(_)
+                if device
+                    .config_scroll_methods()
+                    .contains(&ScrollMethod::OnButtonDown) =>
+            {
+                DeviceSetting::ScrollButtonLock(device.config_scroll_default_button_lock())
+            }
+            DeviceSetting::SendEventsMode(_) => {
+                DeviceSetting::SendEventsMode(device.config_send_events_default_mode())
+            }
+            #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+            DeviceSetting::ThreeFingerDrag(_)
+                if device
+                    .config_3fg_drag_get_finger_count()
+                    .map_or(false, |count| count >= 3) =>
+            {
+                DeviceSetting::ThreeFingerDrag(device.config_3fg_drag_get_default_enabled().ok()?)
+            }
+            DeviceSetting::TapButtonMap(_) if tapping => {
+                DeviceSetting::TapButtonMap(device.config_tap_default_button_map()?)
+            }
+            DeviceSetting::TapDrag(_) if tapping => {
+                DeviceSetting::TapDrag(device.config_tap_default_drag_enabled().ok()?)
+            }
+            DeviceSetting::TapDragLock(_) if tapping => {
+                DeviceSetting::TapDragLock(device.config_tap_default_drag_lock_enabled())
+            }
+            DeviceSetting::Tap(_) if tapping => {
+                DeviceSetting::Tap(device.config_tap_default_enabled().ok()?)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Whether both settings configure the same property of a device.
+    pub fn same_kind(&self, other: &DeviceSetting) -> bool {
+        mem::discriminant(self) == mem::discriminant(other)
+    }
+}
+
+/// A setting that failed to be applied automatically to a device.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ConfigFailure {
+    /// System name of the device, e.g. `event3`
+    pub sysname: String,
+    /// Key of the device
+    pub key: DeviceKey,
+    /// The setting that failed to apply
+    pub setting: DeviceSetting,
+    /// The error returned by the setter
+    pub error: DeviceConfigError,
+}
+
+impl fmt::Display for ConfigFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to apply {:?} to {}: {}",
+            self.setting, self.sysname, self.error
+        )
+    }
+}
+
+impl StdError for ConfigFailure {}
+
+/// Configuration remembered for a context.
+#[derive(Debug, Default)]
+pub(crate) struct ConfigState {
+    enabled: bool,
+    // Set while applying settings that must not be remembered
+    paused: bool,
+    settings: HashMap<DeviceKey, Vec<DeviceSetting>>,
+    failures: Vec<ConfigFailure>,
+    #[cfg(feature = "rules")]
+    pub rules: Option<Rc<DeviceRules>>,
+}
+
+impl ConfigState {
+    fn insert(&mut self, key: DeviceKey, setting: DeviceSetting) {
+        let settings = self.settings.entry(key).or_default();
+        match settings.iter_mut().find(|other| other.same_kind(&setting)) {
+            Some(other) => *other = setting,
+            None => settings.push(setting),
+        }
+    }
+
+    /// The settings remembered for `key`.
+    #[cfg(feature = "rules")]
+    pub fn remembered(&self, key: &DeviceKey) -> &[DeviceSetting] {
+        match self.settings.get(key) {
+            Some(settings) if self.enabled => settings,
+            _ => &[],
+        }
+    }
+
+    /// Whether settings are currently applied by the library itself, e.g.
+    /// from the device rules, rather than by the user through a setter.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Remember a setting successfully applied through a setter.
+    pub fn remember(&mut self, device: &Device, setting: DeviceSetting) {
+        if !self.enabled || self.paused {
+            return;
+        }
+        self.insert(device_key(&device.context(), device), setting);
+    }
+}
+
+pub(crate) fn device_key(context: &Libinput, device: &Device) -> DeviceKey {
+    context
+        .device_registry()
+        .key(device)
+        .unwrap_or_else(|| DeviceKey::from_device(device))
+}
+
+/// Apply settings to `device`, recording failures.
+///
+/// Unless `remember` is set, the settings are not remembered by the store.
+pub(crate) fn apply_settings(
+    state: &RefCell<ConfigState>,
+    device: &Device,
+    key: &DeviceKey,
+    settings: Vec<DeviceSetting>,
+    remember: bool,
+) -> Vec<ConfigFailure> {
+    let paused = mem::replace(&mut state.borrow_mut().paused, !remember);
+    // Setters record into the state again, so it must not be borrowed here
+    let failures = settings
+        .into_iter()
+        .filter_map(|setting| {
+            let error = setting.apply(device).err()?;
+            #[cfg(feature = "log")]
+            log::warn!(
+                "Failed to apply {:?} to {}: {}",
+                setting,
+                device.sysname().unwrap_or_default(),
+                error
+            );
+            Some(ConfigFailure {
+                sysname: device.sysname().unwrap_or_default().to_owned(),
+                key: key.clone(),
+                setting,
+                error,
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut state = state.borrow_mut();
+    state.paused = paused;
+    state.failures.extend(failures.iter().cloned());
+    failures
+}
+
+/// Run `f` without remembering the settings it applies.
+pub(crate) fn without_remembering<T, F: FnOnce() -> T>(context: &Libinput, f: F) -> T {
+    let state = context.config_state();
+    let paused = state.map(|state| mem::replace(&mut state.borrow_mut().paused, true));
+    let ret = f();
+    if let (Some(state), Some(paused)) = (state, paused) {
+        state.borrow_mut().paused = paused;
+    }
+    ret
+}
+
+/// Re-apply the settings stored for `device`, recording failures.
+pub(crate) fn reapply(context: &Libinput, device: &Device) -> Vec<ConfigFailure> {
+    let state = match context.config_state() {
+        Some(state) => state,
+        None => return Vec::new(),
+    };
+    let key = device_key(context, device);
+    let settings = {
+        let state = state.borrow();
+        match state.settings.get(&key) {
+            Some(settings) if state.enabled => settings.clone(),
+            _ => return Vec::new(),
+        }
+    };
+    apply_settings(state, device, &key, settings, true)
+}
+
+/// Configure a device that was just added, applying the device rules
+/// first, the settings stored for it afterwards and the configuration
+/// overrides and inhibitors of its key last.
+pub(crate) fn device_added(context: &Libinput, device: &Device) {
+    #[cfg(feature = "rules")]
+    crate::rules::apply_rules(context, device);
+    reapply(context, device);
+    apply_temporary(context, device);
+}
+
+/// Apply the configuration overrides and inhibitors active for the key of
+/// `device` on top of its rules and stored settings.
+pub(crate) fn apply_temporary(context: &Libinput, device: &Device) {
+    crate::overrides::device_added(context, device);
+    crate::inhibit::device_added(context, device);
+}
+
+/// Configuration store of a context, re-applying settings to devices when
+/// they reappear.
+///
+/// Once enabled, settings successfully applied through the
+/// `Device::config_*` setters are remembered by the [`DeviceKey`] of the
+/// device. Whenever a device is added, e.g. after being replugged or on
+/// [`Libinput::resume`], the settings stored for its key are applied again
+/// while processing its
+/// [`DeviceAddedEvent`](crate::event::device::DeviceAddedEvent). Settings
+/// failing to apply are recorded and can be retrieved with
+/// [`ConfigStore::take_failures`], together with failures of the device
+/// rules set with `Libinput::set_device_rules`, if the `rules` feature is
+/// enabled.
+///
+/// The store is disabled by default. Contexts created with
+/// [`Libinput::from_raw`] have no store.
+///
+/// ```no_run
+/// use input::{interface::DirectInterface, Libinput};
+///
+/// let mut input = Libinput::new_with_udev(DirectInterface::new()).unwrap();
+/// input.config_store().set_enabled(true);
+/// input.udev_assign_seat("seat0").unwrap();
+/// loop {
+///     input.dispatch().unwrap();
+///     for event in &mut input {}
+///     for failure in input.config_store().take_failures() {
+///         eprintln!("{}", failure);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfigStore {
+    context: Libinput,
+}
+
+impl ConfigStore {
+    pub(crate) fn new(context: Libinput) -> ConfigStore {
+        ConfigStore { context }
+    }
+
+    /// Whether settings are remembered and re-applied.
+    pub fn is_enabled(&self) -> bool {
+        self.context
+            .config_state()
+            .map_or(false, |state| state.borrow().enabled)
+    }
+
+    /// Enable or disable remembering and re-applying settings.
+    ///
+    /// Disabling the store keeps the settings remembered so far.
+    pub fn set_enabled(&self, enabled: bool) {
+        if let Some(state) = self.context.config_state() {
+            state.borrow_mut().enabled = enabled;
+        }
+    }
+
+    /// Settings stored for devices with the given key.
+    pub fn settings(&self, key: &DeviceKey) -> Vec<DeviceSetting> {
+        self.context
+            .config_state()
+            .and_then(|state| state.borrow().settings.get(key).cloned())
+            .unwrap_or_default()
+    }
+
+    /// Keys of all devices with stored settings.
+    pub fn keys(&self) -> Vec<DeviceKey> {
+        self.context
+            .config_state()
+            .map(|state| state.borrow().settings.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Store a setting for devices with the given key, replacing a stored
+    /// setting of the same kind.
+    ///
+    /// The setting is applied once a matching device is added, it is not
+    /// applied to devices already present.
+    pub fn insert(&self, key: DeviceKey, setting: DeviceSetting) {
+        if let Some(state) = self.context.config_state() {
+            state.borrow_mut().insert(key, setting);
+        }
+    }
+
+    /// Forget the settings stored for devices with the given key and
+    /// return them.
+    pub fn forget(&self, key: &DeviceKey) -> Vec<DeviceSetting> {
+        self.context
+            .config_state()
+            .and_then(|state| state.borrow_mut().settings.remove(key))
+            .unwrap_or_default()
+    }
+
+    /// Forget all stored settings.
+    pub fn clear(&self) {
+        if let Some(state) = self.context.config_state() {
+            state.borrow_mut().settings.clear();
+        }
+    }
+
+    /// Apply the settings stored for a device again, returning the
+    /// settings that failed to apply.
+    ///
+    /// Failures are recorded as well. Nothing is applied while the store is
+    /// disabled.
+    pub fn reapply(&self, device: &Device) -> Vec<ConfigFailure> {
+        reapply(&self.context, device)
+    }
+
+    /// Settings that failed to be applied automatically, in the order they
+    /// failed.
+    pub fn failures(&self) -> Vec<ConfigFailure> {
+        self.context
+            .config_state()
+            .map(|state| state.borrow().failures.clone())
+            .unwrap_or_default()
+    }
+
+    /// Take the recorded failures, see `failures`.
+    pub fn take_failures(&self) -> Vec<ConfigFailure> {
+        self.context
+            .config_state()
+            .map(|state| mem::take(&mut state.borrow_mut().failures))
+            .unwrap_or_default()
+    }
+}
@@ -1,17 +1,29 @@
-// TODO Error type instead of `Result<_, ()>`
-#![allow(clippy::result_unit_err)]
-
-use crate::{ffi, AsRaw, Device, Event, FromRaw};
+use crate::{
+    config_store::{self, ConfigState},
+    event::{DeviceEvent, EventTrait},
+    ffi,
+    inhibit::InhibitState,
+    overrides::OverrideState,
+    registry::RegistryState,
+    AsRaw, ConfigStore, Device, DeviceRegistry, Error, Event, FromRaw, OpenFailure,
+};
 use std::{
+    any::TypeId,
+    cell::{Cell, RefCell},
     ffi::{CStr, CString},
     io::{Error as IoError, Result as IoResult},
     iter::Iterator,
+    mem,
     os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
     path::Path,
     rc::Rc,
 };
 #[cfg(feature = "udev")]
-use udev::ffi as udev;
+use udev::{AsRaw as UdevAsRaw, Udev};
+
+mod fds;
+
+use self::fds::FdRegistry;
 
 /// libinput does not open file descriptors to devices directly,
 /// instead `open_restricted` and `close_restricted` are called for
@@ -41,6 +53,65 @@ pub trait LibinputInterface {
     fn close_restricted(&mut self, fd: OwnedFd);
 }
 
+/// State shared between a `Libinput` context, its clones and the
+/// callbacks invoked by libinput.
+struct ContextData<I: ?Sized> {
+    fds: RefCell<FdRegistry>,
+    failures: RefCell<Vec<OpenFailure>>,
+    registry: RefCell<RegistryState>,
+    config: RefCell<ConfigState>,
+    overrides: RefCell<OverrideState>,
+    inhibitors: RefCell<InhibitState>,
+    // File descriptors libinput closed while the interface was borrowed
+    deferred_closes: RefCell<Vec<OwnedFd>>,
+    // Set while `path_add_device` is running, libinput drops such devices
+    // if they fail to open
+    adding_path: Cell<bool>,
+    #[cfg(feature = "udev")]
+    udev: Option<Udev>,
+    type_id: TypeId,
+    interface: RefCell<I>,
+}
+
+impl<I: LibinputInterface + 'static> ContextData<I> {
+    fn new(interface: I, #[cfg(feature = "udev")] udev: Option<Udev>) -> Rc<Self> {
+        Rc::new(ContextData {
+            fds: RefCell::new(FdRegistry::default()),
+            failures: RefCell::new(Vec::new()),
+            registry: RefCell::new(RegistryState::default()),
+            config: RefCell::new(ConfigState::default()),
+            overrides: RefCell::new(OverrideState::default()),
+            inhibitors: RefCell::new(InhibitState::default()),
+            deferred_closes: RefCell::new(Vec::new()),
+            adding_path: Cell::new(false),
+            #[cfg(feature = "udev")]
+            udev,
+            type_id: TypeId::of::<I>(),
+            interface: RefCell::new(interface),
+        })
+    }
+}
+
+impl<I: LibinputInterface + ?Sized> ContextData<I> {
+    /// Close a file descriptor through the interface, or once the interface
+    /// is no longer borrowed.
+    fn close_restricted(&self, fd: OwnedFd) {
+        self.deferred_closes.borrow_mut().push(fd);
+        self.close_deferred();
+    }
+
+    /// Close the file descriptors deferred by `close_restricted` unless the
+    /// interface is still borrowed.
+    fn close_deferred(&self) {
+        if let Ok(mut interface) = self.interface.try_borrow_mut() {
+            let fds = mem::take(&mut *self.deferred_closes.borrow_mut());
+            for fd in fds {
+                interface.close_restricted(fd);
+            }
+        }
+    }
+}
+
 unsafe extern "C" fn open_restricted<I: LibinputInterface + 'static>(
     path: *const libc::c_char,
     flags: libc::c_int,
@@ -48,20 +119,45 @@ unsafe extern "C" fn open_restricted<I: LibinputInterface + 'static>(
 ) -> libc::c_int {
     use std::borrow::Cow;
 
-    if let Some(interface) = (user_data as *mut I).as_mut() {
+    if let Some(data) = (user_data as *const ContextData<I>).as_ref() {
         let path_str = CStr::from_ptr(path).to_string_lossy();
-        let res = match path_str {
-            Cow::Borrowed(string) => interface.open_restricted(Path::new(string), flags),
-            Cow::Owned(string) => interface.open_restricted(Path::new(&string), flags),
+        let path = match &path_str {
+            Cow::Borrowed(string) => Path::new(string),
+            Cow::Owned(string) => Path::new(string),
         };
+        data.close_deferred();
+        let registered = data.fds.borrow_mut().open(path, flags);
+        let res = match registered {
+            Some(res) => res,
+            None => match data.interface.try_borrow_mut() {
+                Ok(mut interface) => interface.open_restricted(path, flags),
+                Err(_) => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Failed to open {}, the interface is borrowed",
+                        path.display()
+                    );
+                    Err(libc::EBUSY)
+                }
+            },
+        };
+        let mut failures = data.failures.borrow_mut();
+        failures.retain(|failure| failure.path != path);
         match res {
             Ok(fd) => fd.into_raw_fd(),
             Err(errno) => {
-                if errno > 0 {
-                    -errno
-                } else {
-                    errno
-                }
+                // libinput treats 0 as a valid file descriptor
+                let errno = match errno.abs() {
+                    0 => libc::EIO,
+                    errno => errno,
+                };
+                failures.push(OpenFailure {
+                    path: path.to_path_buf(),
+                    errno,
+                    flags,
+                    path_add: data.adding_path.get(),
+                });
+                -errno
             }
         }
     } else {
@@ -73,8 +169,14 @@ unsafe extern "C" fn close_restricted<I: LibinputInterface + 'static>(
     fd: libc::c_int,
     user_data: *mut libc::c_void,
 ) {
-    if let Some(interface) = (user_data as *mut I).as_mut() {
-        interface.close_restricted(unsafe { OwnedFd::from_raw_fd(fd) })
+    if let Some(data) = (user_data as *const ContextData<I>).as_ref() {
+        let fd = data
+            .fds
+            .borrow_mut()
+            .close(unsafe { OwnedFd::from_raw_fd(fd) });
+        if let Some(fd) = fd {
+            data.close_restricted(fd);
+        }
     }
 }
 
@@ -88,7 +190,7 @@ unsafe extern "C" fn close_restricted<I: LibinputInterface + 'static>(
 /// receive events.
 pub struct Libinput {
     ffi: *mut ffi::libinput,
-    _interface: Option<Rc<dyn LibinputInterface + 'static>>,
+    data: Option<Rc<ContextData<dyn LibinputInterface + 'static>>>,
 }
 
 impl ::std::fmt::Debug for Libinput {
@@ -107,16 +209,26 @@ impl Clone for Libinput {
     fn clone(&self) -> Self {
         Libinput {
             ffi: unsafe { ffi::libinput_ref(self.as_raw_mut()) },
-            _interface: self._interface.clone(),
+            data: self.data.clone(),
         }
     }
 }
 
 impl Drop for Libinput {
     fn drop(&mut self) {
+        if let Some(data) = &self.data {
+            // The last handle releases the devices of the registry while the
+            // context is still alive
+            if Rc::strong_count(data) == 1 {
+                data.registry.borrow_mut().clear();
+            }
+        }
         unsafe {
             ffi::libinput_unref(self.ffi);
         }
+        if let Some(data) = &self.data {
+            data.close_deferred();
+        }
     }
 }
 
@@ -143,10 +255,27 @@ impl Iterator for Libinput {
                 return None;
             } else {
                 match unsafe { Event::try_from_raw(ptr, self) } {
-                    Some(x) => return Some(x),
+                    Some(x) => {
+                        if let (Event::Device(event), Some(data)) = (&x, &self.data) {
+                            match event {
+                                DeviceEvent::Added(event) => {
+                                    let device = event.device();
+                                    data.registry.borrow_mut().add(&device);
+                                    config_store::device_added(self, &device);
+                                }
+                                DeviceEvent::Removed(event) => {
+                                    data.registry.borrow_mut().remove(&event.device())
+                                }
+                            }
+                        }
+                        return Some(x);
+                    }
+                    // Events of types unknown to this library are returned as
+                    // `Event::Unknown`, so this only skips `LIBINPUT_EVENT_NONE`,
+                    // which libinput never returns
                     None => {
                         #[cfg(feature = "log")]
-                        log::warn!("Skipping unknown event: {}", unsafe {
+                        log::warn!("Skipping invalid event: {}", unsafe {
                             ffi::libinput_event_get_type(ptr)
                         });
                         continue;
@@ -165,33 +294,66 @@ impl Libinput {
     /// ## Arguments
     ///
     /// - interface - A `LibinputInterface` providing functions to open and close devices.
-    /// - userdata - Optionally some userdata attached to the newly created context (see [`Userdata`](./trait.Userdata.html))
-    /// - udev_context - Raw pointer to a valid udev context.
     ///
-    /// # Safety
+    /// ## Errors
+    ///
+    /// Returns `Error::Io` if no udev context could be created and
+    /// `Error::ContextCreation` if libinput failed to create the context.
+    #[cfg(feature = "udev")]
+    pub fn new_with_udev<I: LibinputInterface + 'static>(interface: I) -> Result<Libinput, Error> {
+        Libinput::new_with_udev_context(interface, Udev::new()?)
+    }
+
+    /// Create a new libinput context using an existing udev context.
+    ///
+    /// The udev context is kept alive as long as the libinput context, so
+    /// udev state can be shared with other subsystems, e.g. for DRM hotplug.
+    /// It is available through `udev`.
+    ///
+    /// This context is inactive until `udev_assign_seat` is called.
+    ///
+    /// ## Arguments
     ///
-    /// This function is unsafe, because there is no way to verify that `udev_context` is indeed a valid udev context or even points to valid memory.
+    /// - interface - A `LibinputInterface` providing functions to open and close devices.
+    /// - udev - The udev context to use.
+    ///
+    /// ## Errors
+    ///
+    /// Returns `Error::ContextCreation` if libinput failed to create the context.
     #[cfg(feature = "udev")]
-    pub fn new_with_udev<I: LibinputInterface + 'static>(interface: I) -> Libinput {
-        let boxed_userdata = Rc::new(interface);
+    pub fn new_with_udev_context<I: LibinputInterface + 'static>(
+        interface: I,
+        udev: Udev,
+    ) -> Result<Libinput, Error> {
         let boxed_interface = Box::new(ffi::libinput_interface {
             open_restricted: Some(open_restricted::<I>),
             close_restricted: Some(close_restricted::<I>),
         });
+        let udev_ptr = udev.as_raw();
+        let boxed_userdata = ContextData::new(interface, Some(udev));
 
-        unsafe {
-            let udev = udev::udev_new();
-            let libinput = ffi::libinput_udev_create_context(
+        let libinput = unsafe {
+            ffi::libinput_udev_create_context(
                 Box::into_raw(boxed_interface),
                 Rc::as_ptr(&boxed_userdata) as *mut _,
-                udev as *mut input_sys::udev,
-            );
-            udev::udev_unref(udev);
-            Libinput {
-                ffi: libinput,
-                _interface: Some(boxed_userdata as Rc<dyn LibinputInterface>),
-            }
+                udev_ptr as *mut input_sys::udev,
+            )
+        };
+        if libinput.is_null() {
+            return Err(Error::ContextCreation);
         }
+        Ok(Libinput {
+            ffi: libinput,
+            data: Some(boxed_userdata as Rc<ContextData<dyn LibinputInterface>>),
+        })
+    }
+
+    /// The udev context this context was created with, if any.
+    ///
+    /// Returns `None` for contexts created with `new_from_path` or `from_raw`.
+    #[cfg(feature = "udev")]
+    pub fn udev(&self) -> Option<&Udev> {
+        self.data.as_ref()?.udev.as_ref()
     }
 
     /// Create a new libinput context that requires the caller to manually add or remove devices.
@@ -206,21 +368,141 @@ impl Libinput {
     /// - interface - A `LibinputInterface` providing functions to open and close devices.
     /// - userdata - Optionally some userdata attached to the newly created context (see [`Userdata`](./trait.Userdata.html))
     ///
-    pub fn new_from_path<I: 'static + LibinputInterface>(interface: I) -> Libinput {
-        let boxed_userdata = Rc::new(interface);
+    /// ## Errors
+    ///
+    /// Returns `Error::ContextCreation` if libinput failed to create the context.
+    pub fn new_from_path<I: 'static + LibinputInterface>(interface: I) -> Result<Libinput, Error> {
+        let boxed_userdata = ContextData::new(
+            interface,
+            #[cfg(feature = "udev")]
+            None,
+        );
         let boxed_interface = Box::new(ffi::libinput_interface {
             open_restricted: Some(open_restricted::<I>),
             close_restricted: Some(close_restricted::<I>),
         });
 
-        Libinput {
-            ffi: unsafe {
-                ffi::libinput_path_create_context(
-                    Box::into_raw(boxed_interface),
-                    Rc::as_ptr(&boxed_userdata) as *mut _,
-                )
-            },
-            _interface: Some(boxed_userdata as Rc<dyn LibinputInterface>),
+        let libinput = unsafe {
+            ffi::libinput_path_create_context(
+                Box::into_raw(boxed_interface),
+                Rc::as_ptr(&boxed_userdata) as *mut _,
+            )
+        };
+        if libinput.is_null() {
+            return Err(Error::ContextCreation);
+        }
+        Ok(Libinput {
+            ffi: libinput,
+            data: Some(boxed_userdata as Rc<ContextData<dyn LibinputInterface>>),
+        })
+    }
+
+    /// Access the `LibinputInterface` this context was created with.
+    ///
+    /// Returns `None` if the interface is not of type `I` or the context was
+    /// created with `from_raw`.
+    ///
+    /// libinput calls into the interface while dispatching, adding devices or
+    /// resuming the context. Opening a device fails with `EBUSY` if the
+    /// interface is borrowed at that time, and closing it is deferred until
+    /// the next call into the context, so borrows should not be held across
+    /// calls to the context.
+    ///
+    /// ```no_run
+    /// use input::{interface::DirectInterface, Libinput};
+    ///
+    /// let mut input = Libinput::new_with_udev(DirectInterface::new()).unwrap();
+    /// input.udev_assign_seat("seat0").unwrap();
+    ///
+    /// let interface = input.interface::<DirectInterface>().unwrap();
+    /// println!("Open devices: {:?}", interface.borrow().open_paths());
+    /// ```
+    pub fn interface<I: LibinputInterface + 'static>(&self) -> Option<&RefCell<I>> {
+        let data = self.data.as_ref()?;
+        if data.type_id != TypeId::of::<I>() {
+            return None;
+        }
+        let interface = &data.interface as *const RefCell<dyn LibinputInterface>;
+        // Safety: The type of the interface was checked above
+        Some(unsafe { &*(interface as *const RefCell<I>) })
+    }
+
+    /// Devices libinput failed to open through the `LibinputInterface`.
+    ///
+    /// A failure is recorded whenever `LibinputInterface::open_restricted`
+    /// returns an error and is dropped once the same path is opened
+    /// successfully. libinput skips devices that fail to open, so this is the
+    /// only way to learn about e.g. missing permissions.
+    pub fn open_failures(&self) -> Vec<OpenFailure> {
+        self.data
+            .as_ref()
+            .map(|data| data.failures.borrow().clone())
+            .unwrap_or_default()
+    }
+
+    /// Take the recorded open failures, see `open_failures`.
+    pub fn take_open_failures(&mut self) -> Vec<OpenFailure> {
+        self.data
+            .as_ref()
+            .map(|data| data.failures.take())
+            .unwrap_or_default()
+    }
+
+    /// Enumerate and look up the devices of this context.
+    pub fn device_registry(&self) -> DeviceRegistry {
+        DeviceRegistry::new(self.clone())
+    }
+
+    pub(crate) fn registry_state(&self) -> Option<&RefCell<RegistryState>> {
+        self.data.as_ref().map(|data| &data.registry)
+    }
+
+    /// Remember device settings and re-apply them when devices reappear.
+    pub fn config_store(&self) -> ConfigStore {
+        ConfigStore::new(self.clone())
+    }
+
+    pub(crate) fn config_state(&self) -> Option<&RefCell<ConfigState>> {
+        self.data.as_ref().map(|data| &data.config)
+    }
+
+    pub(crate) fn override_state(&self) -> Option<&RefCell<OverrideState>> {
+        self.data.as_ref().map(|data| &data.overrides)
+    }
+
+    pub(crate) fn inhibit_state(&self) -> Option<&RefCell<InhibitState>> {
+        self.data.as_ref().map(|data| &data.inhibitors)
+    }
+
+    /// Open a device node like libinput does, i.e. through the registered
+    /// file descriptors or the `LibinputInterface` of the context.
+    ///
+    /// Contexts without an interface open the node directly. The file
+    /// descriptor must be passed to `close_device_node`.
+    pub(crate) fn open_device_node(&self, path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+        let data = match &self.data {
+            Some(data) => data,
+            None => return crate::interface::open_device(path, flags),
+        };
+        data.close_deferred();
+        if let Some(res) = data.fds.borrow_mut().open(path, flags) {
+            return res;
+        }
+        match data.interface.try_borrow_mut() {
+            Ok(mut interface) => interface.open_restricted(path, flags),
+            Err(_) => Err(libc::EBUSY),
+        }
+    }
+
+    /// Close a file descriptor returned by `open_device_node`.
+    pub(crate) fn close_device_node(&self, fd: OwnedFd) {
+        let data = match &self.data {
+            Some(data) => data,
+            None => return,
+        };
+        let fd = data.fds.borrow_mut().close(fd);
+        if let Some(fd) = fd {
+            data.close_restricted(fd);
         }
     }
 
@@ -232,15 +514,22 @@ impl Libinput {
     /// `path_remove_device()`.
     ///
     /// If the device was successfully initialized, it is returned.
+    /// Returns `None` if the path contains a null-byte.
     ///
     /// ## Warning
     ///
     /// It is an application bug to call this function on a context
     /// initialized with `new_from_udev`.
     pub fn path_add_device(&mut self, path: &str) -> Option<Device> {
-        let path = CString::new(path).expect("Device Path contained a null-byte");
+        let path = CString::new(path).ok()?;
         unsafe {
+            if let Some(data) = &self.data {
+                data.adding_path.set(true);
+            }
             let ptr = ffi::libinput_path_add_device(self.as_raw_mut(), path.as_ptr());
+            if let Some(data) = &self.data {
+                data.adding_path.set(false);
+            }
             if ptr.is_null() {
                 None
             } else {
@@ -249,9 +538,39 @@ impl Libinput {
         }
     }
 
+    /// Add a device from an already opened file descriptor to a libinput
+    /// context initialized with `new_from_path`.
+    ///
+    /// The file descriptor is registered under a synthetic path, which is
+    /// added like with `path_add_device`. Whenever libinput opens the device,
+    /// it receives a duplicate of the file descriptor instead of calling
+    /// `LibinputInterface::open_restricted`, so the device can also be
+    /// reopened on `resume`. The file descriptor is kept until the device is
+    /// removed with `path_remove_device` or the context is dropped.
+    ///
+    /// This allows using devices handed out by a portal or a sandbox broker
+    /// without access to their device nodes.
+    ///
+    /// If the device was successfully initialized, it is returned.
+    ///
+    /// ## Warning
+    ///
+    /// It is an application bug to call this function on a context
+    /// initialized with `new_from_udev`.
+    pub fn path_add_device_fd(&mut self, fd: OwnedFd) -> Option<Device> {
+        let data = self.data.clone()?;
+        let path = data.fds.borrow_mut().register(fd).ok()?;
+        let device = self.path_add_device(path.to_str()?);
+        match device.as_ref().and_then(Device::sysname) {
+            Some(sysname) => data.fds.borrow_mut().set_sysname(&path, sysname),
+            None => data.fds.borrow_mut().unregister(&path),
+        }
+        device
+    }
+
     /// Remove a device from a libinput context initialized with
     /// `new_from_path` and added to such a context with
-    /// `path_add_device`.
+    /// `path_add_device` or `path_add_device_fd`.
     ///
     /// Events already processed from this input device are kept in
     /// the queue, the `DeviceRemovedEvent` event marks the end of
@@ -262,7 +581,11 @@ impl Libinput {
     /// It is an application bug to call this function on a context
     /// initialized with `new_from_udev`.
     pub fn path_remove_device(&mut self, device: Device) {
+        let sysname = device.sysname().map(str::to_owned);
         unsafe { ffi::libinput_path_remove_device(device.as_raw_mut()) }
+        if let (Some(data), Some(sysname)) = (&self.data, sysname) {
+            data.fds.borrow_mut().unregister_sysname(&sysname);
+        }
     }
 
     /// Assign a seat to this libinput context.
@@ -282,13 +605,12 @@ impl Libinput {
     ///
     /// This function may only be called once per context.
     #[cfg(feature = "udev")]
-    pub fn udev_assign_seat(&mut self, seat_id: &str) -> Result<(), ()> {
-        let id = CString::new(seat_id).expect("Seat Id contained a null-byte");
+    pub fn udev_assign_seat(&mut self, seat_id: &str) -> Result<(), Error> {
+        let id = CString::new(seat_id)?;
         unsafe {
             match ffi::libinput_udev_assign_seat(self.as_raw_mut(), id.as_ptr()) {
                 0 => Ok(()),
-                -1 => Err(()),
-                _ => unreachable!(),
+                _ => Err(Error::SeatAssignment),
             }
         }
     }
@@ -304,12 +626,11 @@ impl Libinput {
     /// Resume a suspended libinput context.
     ///
     /// This re-enables device monitoring and adds existing devices.
-    pub fn resume(&mut self) -> Result<(), ()> {
+    pub fn resume(&mut self) -> Result<(), Error> {
         unsafe {
             match ffi::libinput_resume(self.as_raw_mut()) {
                 0 => Ok(()),
-                -1 => Err(()),
-                _ => unreachable!(),
+                _ => Err(Error::Resume),
             }
         }
     }
@@ -328,11 +649,13 @@ impl Libinput {
     /// calling `dispatch` may prevent these features from working
     /// correctly.
     pub fn dispatch(&mut self) -> IoResult<()> {
+        if let Some(data) = &self.data {
+            data.close_deferred();
+        }
         unsafe {
             match ffi::libinput_dispatch(self.as_raw_mut()) {
-                0 => Ok(()),
                 x if x < 0 => Err(IoError::from_raw_os_error(-x)),
-                _ => unreachable!(),
+                _ => Ok(()),
             }
         }
     }
@@ -381,7 +704,7 @@ impl Libinput {
     /// #     std::process::exit(0);
     /// # });
     /// #
-    /// let mut input = Libinput::new_with_udev(Interface);
+    /// let mut input = Libinput::new_with_udev(Interface).unwrap();
     /// input.udev_assign_seat("seat0").unwrap();
     ///
     /// while poll(&mut [PollFd::new(&input, PollFlags::IN)], None).is_ok() {
@@ -422,7 +745,7 @@ impl Libinput {
     pub unsafe fn from_raw(ffi: *mut ffi::libinput) -> Self {
         Libinput {
             ffi: ffi::libinput_ref(ffi),
-            _interface: None,
+            data: None,
         }
     }
 }
@@ -445,10 +768,13 @@ impl Libinput {
 
     /// Appends the given directory path to the libinput plugin lookup path.
     /// If the path is already in the lookup paths, this function does nothing.
+    ///
+    /// Returns `Error::Nul` if the path contains a null-byte.
     #[doc(alias = "libinput_plugin_system_append_path")]
-    pub fn plugin_system_append_path(&self, path: &str) {
-        let path = CString::new(path).expect("Plugin path contained a null-byte");
+    pub fn plugin_system_append_path(&self, path: &str) -> Result<(), Error> {
+        let path = CString::new(path)?;
         unsafe { ffi::libinput_plugin_system_append_path(self.as_raw_mut(), path.as_ptr()) };
+        Ok(())
     }
 
     /// Load the plugins from the set of lookup paths.
@@ -458,9 +784,8 @@ impl Libinput {
     pub fn plugin_system_load_plugins(&self, flags: PluginSystemFlags) -> IoResult<()> {
         unsafe {
             match ffi::libinput_plugin_system_load_plugins(self.as_raw_mut(), flags.bits()) {
-                0 => Ok(()),
                 x if x < 0 => Err(IoError::from_raw_os_error(-x)),
-                _ => unreachable!(),
+                _ => Ok(()),
             }
         }
     }
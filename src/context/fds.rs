@@ -0,0 +1,142 @@
+use std::{
+    collections::HashSet,
+    ffi::CString,
+    fs,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    mem,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsRawFd, OwnedFd, RawFd},
+    },
+    path::{Path, PathBuf},
+};
+
+/// A device file descriptor registered with `Libinput::path_add_device_fd`.
+#[derive(Debug)]
+struct Entry {
+    fd: OwnedFd,
+    /// Synthetic path the device was added under
+    path: PathBuf,
+    /// Path the file descriptor was originally opened with, if known
+    target: Option<PathBuf>,
+    rdev: libc::dev_t,
+    /// System name of the device created for the file descriptor
+    sysname: Option<String>,
+}
+
+/// Pre-opened device file descriptors handed to libinput instead of
+/// opening devices through the `LibinputInterface`.
+///
+/// libinput receives duplicates of the registered file descriptors, so the
+/// devices can be reopened on `Libinput::resume`.
+#[derive(Debug, Default)]
+pub(crate) struct FdRegistry {
+    entries: Vec<Entry>,
+    handed_out: HashSet<RawFd>,
+}
+
+fn char_device(stat: &libc::stat) -> Option<libc::dev_t> {
+    (stat.st_mode & libc::S_IFMT == libc::S_IFCHR).then_some(stat.st_rdev)
+}
+
+fn device_number(path: &Path) -> Option<libc::dev_t> {
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::stat = unsafe { mem::zeroed() };
+    if unsafe { libc::stat(path.as_ptr(), &mut stat) } < 0 {
+        return None;
+    }
+    char_device(&stat)
+}
+
+impl FdRegistry {
+    /// Register a device file descriptor and return the synthetic path it
+    /// is reachable under.
+    ///
+    /// The path points to the file descriptor in `/proc/self/fd`, so libinput
+    /// resolves it to the device node even if that is not accessible itself.
+    pub fn register(&mut self, fd: OwnedFd) -> IoResult<PathBuf> {
+        let mut stat: libc::stat = unsafe { mem::zeroed() };
+        if unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } < 0 {
+            return Err(IoError::last_os_error());
+        }
+        let rdev = char_device(&stat)
+            .ok_or_else(|| IoError::new(ErrorKind::InvalidInput, "Not a device node"))?;
+        let path = PathBuf::from(format!("/proc/self/fd/{}", fd.as_raw_fd()));
+        let target = fs::read_link(&path).ok();
+        self.entries.push(Entry {
+            fd,
+            path: path.clone(),
+            target,
+            rdev,
+            sysname: None,
+        });
+        Ok(path)
+    }
+
+    /// Remember the system name of the device added for a registered path.
+    pub fn set_sysname(&mut self, path: &Path, sysname: &str) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) {
+            entry.sysname = Some(sysname.to_owned());
+        }
+    }
+
+    /// Drop the registration of a path that could not be added.
+    pub fn unregister(&mut self, path: &Path) {
+        self.entries.retain(|entry| entry.path != path);
+    }
+
+    /// Drop the registration of a removed device.
+    pub fn unregister_sysname(&mut self, sysname: &str) {
+        self.entries
+            .retain(|entry| entry.sysname.as_deref() != Some(sysname));
+    }
+
+    /// Hand out a registered device opened by libinput.
+    ///
+    /// libinput opens the device node of the device, which is matched
+    /// against the registered file descriptors by path and device number.
+    /// Returns `None` if the path does not belong to a registered device.
+    pub fn open(&mut self, path: &Path, flags: i32) -> Option<Result<OwnedFd, i32>> {
+        let mut rdev = None;
+        let entry = self.entries.iter().find(|entry| {
+            entry.path == path
+                || entry.target.as_deref() == Some(path)
+                || *rdev.get_or_insert_with(|| device_number(path)) == Some(entry.rdev)
+        })?;
+        let result = entry
+            .fd
+            .try_clone()
+            .and_then(|fd| {
+                // The duplicate shares the file status flags of the registered fd
+                let status = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+                if status < 0
+                    || unsafe {
+                        libc::fcntl(
+                            fd.as_raw_fd(),
+                            libc::F_SETFL,
+                            status | (flags & libc::O_NONBLOCK),
+                        )
+                    } < 0
+                {
+                    return Err(IoError::last_os_error());
+                }
+                Ok(fd)
+            })
+            .map_err(|err| err.raw_os_error().unwrap_or(libc::EIO));
+        if let Ok(fd) = &result {
+            self.handed_out.insert(fd.as_raw_fd());
+        }
+        Some(result)
+    }
+
+    /// Take back a file descriptor closed by libinput.
+    ///
+    /// Returns the file descriptor if it was not handed out by the registry.
+    pub fn close(&mut self, fd: OwnedFd) -> Option<OwnedFd> {
+        if self.handed_out.remove(&fd.as_raw_fd()) {
+            None
+        } else {
+            Some(fd)
+        }
+    }
+}
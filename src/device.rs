@@ -1,22 +1,19 @@
 // Allow unnecessary casts since ffi types may differ by C ABI
-// TODO Error type instead of `Result<_, ()>`
 // TODO Better way to handle `SendEventsMode::ENABLED` being 0?
-#![allow(
-    clippy::bad_bit_mask,
-    clippy::result_unit_err,
-    clippy::unnecessary_cast
-)]
+#![allow(clippy::bad_bit_mask, clippy::unnecessary_cast)]
 
 #[cfg(feature = "libinput_1_23")]
 use crate::accel_config::AccelConfig;
 use crate::{
     event::{switch::Switch, tablet_pad::TabletPadModeGroup},
-    ffi, AsRaw, FromRaw, Libinput, Seat,
+    ffi, AsRaw, DeviceSetting, Error, FromRaw, Libinput, Seat,
 };
 use bitflags::bitflags;
 use std::{
     borrow::Cow,
+    error::Error as StdError,
     ffi::{CStr, CString},
+    fmt,
 };
 #[cfg(feature = "udev")]
 use udev::{
@@ -134,15 +131,23 @@ pub enum ScrollMethod {
 
 /// Errors returned when applying configuration settings.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum DeviceConfigError {
     /// Configuration not available on this device.
     Unsupported,
     /// Invalid parameter range.
     Invalid,
+    /// libinput returned a status unknown to this library.
+    Unknown(u32),
+    /// libinput returned a configuration state unknown to this library,
+    /// e.g. when querying whether an option is enabled.
+    UnknownState(u32),
+    /// The configuration option is not provided by the libinput library in
+    /// use, see the `dlopen` feature.
+    UnsupportedVersion,
 }
 
 impl DeviceConfigError {
-    #[cfg(feature = "libinput_1_29")]
     pub(crate) fn from_ffi(v: ffi::libinput_config_status) -> DeviceConfigResult {
         match v {
             ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
@@ -152,11 +157,46 @@ impl DeviceConfigError {
             ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
                 Err(DeviceConfigError::Invalid)
             }
-            _ => unreachable!("libinput returned invalid 'libinput_config_status'"),
+            x => Err(DeviceConfigError::Unknown(x as u32)),
+        }
+    }
+
+    fn as_ffi(&self) -> Option<ffi::libinput_config_status> {
+        match self {
+            DeviceConfigError::Unsupported => {
+                Some(ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED)
+            }
+            DeviceConfigError::Invalid => {
+                Some(ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID)
+            }
+            DeviceConfigError::Unknown(x) => Some(*x as ffi::libinput_config_status),
+            DeviceConfigError::UnsupportedVersion | DeviceConfigError::UnknownState(_) => None,
         }
     }
 }
 
+impl fmt::Display for DeviceConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match (self, self.as_ffi()) {
+            (_, Some(status)) => status,
+            (DeviceConfigError::UnknownState(x), None) => {
+                return write!(f, "Unknown configuration state ({})", x);
+            }
+            (_, None) => {
+                return f.write_str("Configuration not supported by this libinput version");
+            }
+        };
+        let ptr = unsafe { ffi::libinput_config_status_to_str(status) };
+        if ptr.is_null() {
+            write!(f, "Unknown configuration status ({})", status)
+        } else {
+            f.write_str(&unsafe { CStr::from_ptr(ptr) }.to_string_lossy())
+        }
+    }
+}
+
+impl StdError for DeviceConfigError {}
+
 bitflags! {
     /// The send-event mode of a device defines when a device may generate
     /// events and pass those events to the caller.
@@ -205,6 +245,8 @@ pub enum ClickfingerButtonMap {
     LeftRightMiddle,
     /// 1/2/3 finger click maps to left/middle/right
     LeftMiddleRight,
+    /// A mapping unknown to this library
+    Unknown(u32),
 }
 
 /// Drag lock state
@@ -220,12 +262,14 @@ pub enum DragLockState {
     /// or is currently enabled in sticky mode
     #[cfg(feature = "libinput_1_27")]
     EnabledSticky,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 /// A config status to distinguish or set 3-finger dragging on a device.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
-#[cfg(feature = "libinput_1_28")]
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
 pub enum ThreeFingerDragState {
     /// Drag is to be disabled, or is currently disabled
     Disabled,
@@ -233,6 +277,26 @@ pub enum ThreeFingerDragState {
     EnabledThreeFinger,
     /// Drag is to be enabled for 4 fingers, or is currently enabled
     EnabledFourFinger,
+    /// A state unknown to this library
+    Unknown(u32),
+}
+
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+impl ThreeFingerDragState {
+    fn from_ffi(v: ffi::libinput_config_3fg_drag_state) -> Self {
+        match v {
+            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED => {
+                ThreeFingerDragState::Disabled
+            }
+            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG => {
+                ThreeFingerDragState::EnabledThreeFinger
+            }
+            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG => {
+                ThreeFingerDragState::EnabledFourFinger
+            }
+            x => ThreeFingerDragState::Unknown(x as u32),
+        }
+    }
 }
 
 /// Whenever scroll button lock is enabled or not
@@ -242,6 +306,8 @@ pub enum ThreeFingerDragState {
 pub enum ScrollButtonLockState {
     Disabled,
     Enabled,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 /// Result returned when applying configuration settings.
@@ -274,7 +340,7 @@ bitflags! {
 ///
 /// The conditions x1 < x2 and y1 < y2 must be true.
 #[derive(Debug, Copy, Clone, PartialEq)]
-#[cfg(feature = "libinput_1_27")]
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
 pub struct AreaRectangle {
     /// x1 coordinate
     pub x1: f64,
@@ -286,7 +352,18 @@ pub struct AreaRectangle {
     pub y2: f64,
 }
 
-#[cfg(feature = "libinput_1_27")]
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+impl AreaRectangle {
+    /// The rectangle covering the whole device.
+    pub const FULL: AreaRectangle = AreaRectangle {
+        x1: 0.0,
+        y1: 0.0,
+        x2: 1.0,
+        y2: 1.0,
+    };
+}
+
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
 impl From<AreaRectangle> for ffi::libinput_config_area_rectangle {
     fn from(rect: AreaRectangle) -> Self {
         input_sys::libinput_config_area_rectangle {
@@ -298,7 +375,7 @@ impl From<AreaRectangle> for ffi::libinput_config_area_rectangle {
     }
 }
 
-#[cfg(feature = "libinput_1_27")]
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
 impl From<ffi::libinput_config_area_rectangle> for AreaRectangle {
     fn from(rect: ffi::libinput_config_area_rectangle) -> Self {
         AreaRectangle {
@@ -325,8 +402,8 @@ ffi_ref_struct!(
 /// All devices are part of a device group though for most devices the
 /// group will be a singleton. A device is assigned to a device group
 /// on `DeviceAddedEvent` and removed from that group on
-/// `DeviceRemovedEvent`. It is up to the caller to track how many
-/// devices are in each device group.
+/// `DeviceRemovedEvent`. The devices currently in a group are listed by
+/// `DeviceGroup::devices`.
 ///
 /// Device groups do not get re-used once the last device in the group
 /// was removed, i.e. unplugging and re-plugging a physical device
@@ -337,6 +414,60 @@ ffi_ref_struct!(
 /// property, see [Static device configuration](https://wayland.freedesktop.org/libinput/doc/latest/udev_config.html) via udev.
 struct DeviceGroup, ffi::libinput_device_group, ffi::libinput_device_group_ref, ffi::libinput_device_group_unref);
 
+impl DeviceGroup {
+    /// Get the devices currently assigned to this group.
+    ///
+    /// Devices are tracked by the context's [`DeviceRegistry`](crate::DeviceRegistry), so this
+    /// lists the devices whose `DeviceAddedEvent` was processed and whose
+    /// `DeviceRemovedEvent` was not yet processed.
+    pub fn devices(&self) -> Vec<Device> {
+        self.context
+            .device_registry()
+            .devices()
+            .into_iter()
+            .filter(|device| device.device_group() == *self)
+            .collect()
+    }
+
+    fn config_set<A, S>(&self, available: A, set: S) -> Vec<(Device, DeviceConfigResult)>
+    where
+        A: Fn(&Device) -> bool,
+        S: Fn(&Device) -> DeviceConfigResult,
+    {
+        self.devices()
+            .into_iter()
+            .filter(|device| available(device))
+            .map(|device| {
+                let result = set(&device);
+                (device, result)
+            })
+            .collect()
+    }
+
+    /// Set the left-handed configuration of every device in this group
+    /// supporting it.
+    ///
+    /// Returns the result of `Device::config_left_handed_set` for each of
+    /// these devices. Devices without a left-handed configuration are
+    /// skipped.
+    pub fn config_left_handed_set(&self, enabled: bool) -> Vec<(Device, DeviceConfigResult)> {
+        self.config_set(Device::config_left_handed_is_available, |device| {
+            device.config_left_handed_set(enabled)
+        })
+    }
+
+    /// Set the rotation of every device in this group supporting it.
+    ///
+    /// Returns the result of `Device::config_rotation_set_angle` for each
+    /// of these devices. Devices without a rotation configuration are
+    /// skipped.
+    pub fn config_rotation_set_angle(&self, angle: u32) -> Vec<(Device, DeviceConfigResult)> {
+        self.config_set(Device::config_rotation_is_available, |device| {
+            device.config_rotation_set_angle(angle)
+        })
+    }
+}
+
 ffi_ref_struct!(
 /// Representation of a single input device as seen by the kernel.
 ///
@@ -351,6 +482,24 @@ impl Device {
         self.context.clone()
     }
 
+    /// Record a successfully applied setting in the context's `ConfigStore`
+    /// and as the value restored after configuration overrides.
+    fn remember(&self, setting: DeviceSetting, result: DeviceConfigResult) -> DeviceConfigResult {
+        if result.is_ok() {
+            let paused = self.context.config_state().map_or(false, |state| {
+                let mut state = state.borrow_mut();
+                state.remember(self, setting);
+                state.is_paused()
+            });
+            // Settings applied by the library, e.g. from the device rules,
+            // are not the value the user expects back after an override
+            if !paused {
+                crate::overrides::setting_changed(self, setting);
+            }
+        }
+        result
+    }
+
     /// Get the device group this device is assigned to.
     ///
     /// Some physical devices like graphics tablets are represented by
@@ -388,11 +537,13 @@ impl Device {
     /// Get the system name of the device.
     ///
     /// To get the descriptive device name, use `name`.
-    pub fn sysname(&self) -> &str {
+    ///
+    /// Returns `None` if the system name is not valid utf8.
+    pub fn sysname(&self) -> Option<&str> {
         unsafe {
             CStr::from_ptr(ffi::libinput_device_get_sysname(self.as_raw_mut()))
                 .to_str()
-                .expect("Device sysname is no valid utf8")
+                .ok()
         }
     }
 
@@ -473,13 +624,13 @@ impl Device {
     /// ## Note
     /// This change applies to this device until removal or `suspend`,
     /// whichever happens earlier.
-    pub fn set_seat_logical_name(&mut self, name: &str) -> Result<(), ()> {
-        let name = CString::new(name).expect("New logical_seat name contained a null-byte");
+    pub fn set_seat_logical_name(&mut self, name: &str) -> Result<(), Error> {
+        let name = CString::new(name)?;
         unsafe {
             if ffi::libinput_device_set_seat_logical_name(self.as_raw_mut(), name.as_ptr()) == 0 {
                 Ok(())
             } else {
-                Err(())
+                Err(Error::SeatChange)
             }
         }
     }
@@ -494,20 +645,21 @@ impl Device {
     /// Calling this function multiple times for the same device may
     /// not return the same udev handle each time.
     ///
-    /// # Safety
-    ///
-    /// The result of this function is not definied if the passed udev `Context`
-    /// is not the same as the one the libinput `Context` was created from.
+    /// The returned device belongs to the udev context of the libinput
+    /// context, see [`Libinput::new_with_udev_context`](crate::Libinput::new_with_udev_context)
+    /// to share it with other subsystems.
     #[cfg(feature = "udev")]
-    pub unsafe fn udev_device(&self) -> Option<UdevDevice> {
-        let dev: *mut udev_device = ffi::libinput_device_get_udev_device(self.ffi) as *mut _;
-        if dev.is_null() {
-            None
-        } else {
-            // We have to ref the returned udev context as udev_device_get_udev does not
-            // increase the ref_count but dropping a UdevDevice will unref it
-            let ctx: *mut udev_context = udev_ref(udev_device_get_udev(dev));
-            Some(UdevDevice::from_raw_with_context(ctx, dev))
+    pub fn udev_device(&self) -> Option<UdevDevice> {
+        unsafe {
+            let dev: *mut udev_device = ffi::libinput_device_get_udev_device(self.ffi) as *mut _;
+            if dev.is_null() {
+                None
+            } else {
+                // We have to ref the returned udev context as udev_device_get_udev does not
+                // increase the ref_count but dropping a UdevDevice will unref it
+                let ctx: *mut udev_context = udev_ref(udev_device_get_udev(dev));
+                Some(UdevDevice::from_raw_with_context(ctx, dev))
+            }
         }
     }
 
@@ -580,34 +732,37 @@ impl Device {
 
     /// Check if a `DeviceCapability::Pointer` device has a button
     /// with the given code (see linux/input.h).
-    pub fn pointer_has_button(&self, button: u32) -> Result<bool, ()> {
+    ///
+    /// Returns `Error::MissingCapability` if the device lacks the
+    /// `DeviceCapability::Pointer` capability.
+    pub fn pointer_has_button(&self, button: u32) -> Result<bool, Error> {
         match unsafe { ffi::libinput_device_pointer_has_button(self.as_raw_mut(), button) } {
-            1 => Ok(true),
-            0 => Ok(false),
-            -1 => Err(()),
-            _ => unreachable!(),
+            x if x < 0 => Err(Error::MissingCapability(DeviceCapability::Pointer)),
+            x => Ok(x != 0),
         }
     }
 
     /// Check if a `DeviceCapability::Keyboard` device has a key with
     /// the given code (see linux/input.h).
-    pub fn keyboard_has_key(&self, key: u32) -> Result<bool, ()> {
+    ///
+    /// Returns `Error::MissingCapability` if the device lacks the
+    /// `DeviceCapability::Keyboard` capability.
+    pub fn keyboard_has_key(&self, key: u32) -> Result<bool, Error> {
         match unsafe { ffi::libinput_device_keyboard_has_key(self.as_raw_mut(), key) } {
-            1 => Ok(true),
-            0 => Ok(false),
-            -1 => Err(()),
-            _ => unreachable!(),
+            x if x < 0 => Err(Error::MissingCapability(DeviceCapability::Keyboard)),
+            x => Ok(x != 0),
         }
     }
 
     /// Check if a `DeviceCapability::Switch` device has a switch of the
     /// given type.
-    pub fn switch_has_switch(&self, switch: Switch) -> Result<bool, ()> {
+    ///
+    /// Returns `Error::MissingCapability` if the device lacks the
+    /// `DeviceCapability::Switch` capability.
+    pub fn switch_has_switch(&self, switch: Switch) -> Result<bool, Error> {
         match unsafe { ffi::libinput_device_switch_has_switch(self.as_raw_mut(), switch as u32) } {
-            1 => Ok(true),
-            0 => Ok(false),
-            -1 => Err(()),
-            _ => unreachable!(),
+            x if x < 0 => Err(Error::MissingCapability(DeviceCapability::Switch)),
+            x => Ok(x != 0),
         }
     }
 
@@ -668,12 +823,8 @@ impl Device {
     #[cfg(feature = "libinput_1_15")]
     pub fn tablet_pad_has_key(&self, code: u32) -> Option<bool> {
         match unsafe { ffi::libinput_device_tablet_pad_has_key(self.as_raw_mut(), code) } {
-            -1 => None,
-            0 => Some(false),
-            1 => Some(true),
-            _ => panic!(
-                "libinput returned invalid return code for libinput_device_tablet_pad_has_key"
-            ),
+            x if x < 0 => None,
+            x => Some(x != 0),
         }
     }
 
@@ -698,18 +849,9 @@ impl Device {
     /// settings.
     #[cfg(feature = "libinput_1_23")]
     pub fn config_accel_apply(&self, accel_config: AccelConfig) -> DeviceConfigResult {
-        match unsafe {
+        DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_accel_apply(self.as_raw_mut(), accel_config.as_raw_mut())
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        })
     }
 
     /// Return the default pointer acceleration profile for this
@@ -792,7 +934,7 @@ impl Device {
     /// Set the pointer acceleration profile of this pointer device to
     /// the given mode.
     pub fn config_accel_set_profile(&mut self, profile: AccelProfile) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_accel_set_profile(
                 self.as_raw_mut(),
                 match profile {
@@ -808,16 +950,8 @@ impl Device {
                     }
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::AccelProfile(profile), result)
     }
 
     ffi_func!(
@@ -843,16 +977,10 @@ impl Device {
     /// acceleration step if the requested value does not match a
     /// discrete setting.
     pub fn config_accel_set_speed(&mut self, speed: f64) -> DeviceConfigResult {
-        match unsafe { ffi::libinput_device_config_accel_set_speed(self.as_raw_mut(), speed) } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        let result = DeviceConfigError::from_ffi(unsafe {
+            ffi::libinput_device_config_accel_set_speed(self.as_raw_mut(), speed)
+        });
+        self.remember(DeviceSetting::AccelSpeed(speed), result)
     }
 
     ffi_func!(
@@ -898,10 +1026,15 @@ impl Device {
     /// Check if the device can be calibrated via a calibration matrix.
     pub fn config_calibration_has_matrix, ffi::libinput_device_config_calibration_has_matrix, bool);
 
-    #[cfg(feature = "libinput_1_27")]
-    ffi_func!(
     /// Check if the device can change its logical input area via a rectangle.
-    pub fn config_area_has_rectangle, ffi::libinput_device_config_area_has_rectangle, bool);
+    ///
+    /// With the `dlopen` feature, this returns `false` if the libinput library
+    /// in use does not support area configuration.
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+    pub fn config_area_has_rectangle(&self) -> bool {
+        unsafe { ffi_dyn!(libinput_device_config_area_has_rectangle(self.as_raw_mut())) }
+            .map_or(false, |x| x != 0)
+    }
 
     /// Set the given rectangle as the logical input area of this device.
     ///
@@ -942,39 +1075,58 @@ impl Device {
     ///
     /// Changing the area may not take effect immediately, the device may wait until it is in a
     /// neutral state before applying any changes.
-    #[cfg(feature = "libinput_1_27")]
+    ///
+    /// With the `dlopen` feature, this returns
+    /// [`DeviceConfigError::UnsupportedVersion`] if the libinput library in use
+    /// does not support area configuration.
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
     pub fn config_area_set_rectangle(&self, area: AreaRectangle) -> DeviceConfigResult {
-        let area = area.into();
-        match unsafe {
-            ffi::libinput_device_config_area_set_rectangle(self.as_raw_mut(), &raw const area)
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
+        let rect = area.into();
+        let result = unsafe {
+            ffi_dyn!(libinput_device_config_area_set_rectangle(
+                self.as_raw_mut(),
+                &raw const rect
+            ))
         }
+        .map_or(
+            Err(DeviceConfigError::UnsupportedVersion),
+            DeviceConfigError::from_ffi,
+        );
+        self.remember(DeviceSetting::Area(area), result)
     }
 
     /// Return the current area rectangle for this device.
     ///
     /// The return value for a device that does not support area rectangles is
     /// a rectangle with the points 0/0 and 1/1.
-    #[cfg(feature = "libinput_1_27")]
-    pub fn config_area_get_rectangle(&self) -> AreaRectangle {
-        unsafe { ffi::libinput_device_config_area_get_rectangle(self.as_raw_mut()) }.into()
+    ///
+    /// With the `dlopen` feature, this returns
+    /// [`DeviceConfigError::UnsupportedVersion`] if the libinput library in use
+    /// does not support area configuration.
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+    pub fn config_area_get_rectangle(&self) -> Result<AreaRectangle, DeviceConfigError> {
+        unsafe { ffi_dyn!(libinput_device_config_area_get_rectangle(self.as_raw_mut())) }
+            .map(Into::into)
+            .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
     /// Return the default area rectangle for this device.
     ///
     /// The return value for a device that does not support area rectangles is
     /// a rectangle with the points 0/0 and 1/1.
-    #[cfg(feature = "libinput_1_27")]
-    pub fn config_area_get_default_rectangle(&self) -> AreaRectangle {
-        unsafe { ffi::libinput_device_config_area_get_default_rectangle(self.as_raw_mut()) }.into()
+    ///
+    /// With the `dlopen` feature, this returns
+    /// [`DeviceConfigError::UnsupportedVersion`] if the libinput library in use
+    /// does not support area configuration.
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+    pub fn config_area_get_default_rectangle(&self) -> Result<AreaRectangle, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_device_config_area_get_default_rectangle(
+                self.as_raw_mut()
+            ))
+        }
+        .map(Into::into)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
     /// Apply the 3x3 transformation matrix to absolute device
@@ -1018,18 +1170,10 @@ impl Device {
     /// [ 0  0 1]               [  0  0 1]              [  0 0 1 ]
     /// ```
     pub fn config_calibration_set_matrix(&mut self, matrix: [f32; 6]) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_calibration_set_matrix(self.as_raw_mut(), matrix.as_ptr())
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::CalibrationMatrix(matrix), result)
     }
 
     /// Get the default button click method for this device.
@@ -1117,7 +1261,7 @@ impl Device {
     /// device may require changing to a neutral state first before
     /// activating the new method.
     pub fn config_click_set_method(&mut self, method: ClickMethod) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_click_set_method(
                 self.as_raw_mut(),
                 match method {
@@ -1129,16 +1273,8 @@ impl Device {
                     }
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::ClickMethod(method), result)
     }
 
     /// Get the finger number to button number mapping for clickfinger.
@@ -1156,7 +1292,7 @@ impl Device {
             ffi::libinput_config_clickfinger_button_map_LIBINPUT_CONFIG_CLICKFINGER_MAP_LMR => {
                 ClickfingerButtonMap::LeftMiddleRight
             }
-            _ => panic!("libinput returned invalid 'libinput_config_tap_button_map'"),
+            x => ClickfingerButtonMap::Unknown(x as u32),
         }
     }
 
@@ -1175,7 +1311,7 @@ impl Device {
             ffi::libinput_config_clickfinger_button_map_LIBINPUT_CONFIG_CLICKFINGER_MAP_LMR => {
                 ClickfingerButtonMap::LeftMiddleRight
             }
-            _ => panic!("libinput returned invalid 'libinput_config_tap_button_map'"),
+            x => ClickfingerButtonMap::Unknown(x as u32),
         }
     }
 
@@ -1194,21 +1330,14 @@ impl Device {
         &self,
         map: ClickfingerButtonMap,
     ) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_click_set_clickfinger_button_map(self.as_raw_mut(), match map {
                 ClickfingerButtonMap::LeftRightMiddle => ffi::libinput_config_clickfinger_button_map_LIBINPUT_CONFIG_CLICKFINGER_MAP_LRM,
                 ClickfingerButtonMap::LeftMiddleRight => ffi::libinput_config_clickfinger_button_map_LIBINPUT_CONFIG_CLICKFINGER_MAP_LMR,
+                ClickfingerButtonMap::Unknown(x) => x as ffi::libinput_config_clickfinger_button_map,
             })
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::ClickfingerButtonMap(map), result)
     }
 
     /// Check if the disable-while typing feature is enabled on this
@@ -1216,11 +1345,11 @@ impl Device {
     ///
     /// If the device does not support disable-while-typing, this
     /// function returns `false`.
-    pub fn config_dwt_default_enabled(&self) -> bool {
+    pub fn config_dwt_default_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_dwt_get_default_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_ENABLED => true,
-            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_dwt_state'"),
+            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_ENABLED => Ok(true),
+            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1229,11 +1358,11 @@ impl Device {
     ///
     /// If the device does not support disable-while-typing, this
     /// function returns `false`.
-    pub fn config_dwt_enabled(&self) -> bool {
+    pub fn config_dwt_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_dwt_get_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_ENABLED => true,
-            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_dwt_state'"),
+            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_ENABLED => Ok(true),
+            ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1257,7 +1386,7 @@ impl Device {
     /// Enabling or disabling disable-while-typing may not take
     /// effect immediately.
     pub fn config_dwt_set_enabled(&self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_dwt_set_enabled(
                 self.as_raw_mut(),
                 if enabled {
@@ -1266,16 +1395,8 @@ impl Device {
                     ffi::libinput_config_dwt_state_LIBINPUT_CONFIG_DWT_DISABLED
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::Dwt(enabled), result)
     }
 
     /// Check if the disable-while trackpointing feature is enabled on this
@@ -1284,11 +1405,11 @@ impl Device {
     /// If the device does not support disable-while-trackpointing, this
     /// function returns `false`.
     #[cfg(feature = "libinput_1_21")]
-    pub fn config_dwtp_default_enabled(&self) -> bool {
+    pub fn config_dwtp_default_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_dwtp_get_default_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_ENABLED => true,
-            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_dwtp_state'"),
+            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_ENABLED => Ok(true),
+            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1298,11 +1419,11 @@ impl Device {
     /// If the device does not support disable-while-trackpointing, this
     /// function returns `false`.
     #[cfg(feature = "libinput_1_21")]
-    pub fn config_dwtp_enabled(&self) -> bool {
+    pub fn config_dwtp_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_dwtp_get_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_ENABLED => true,
-            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_dwtp_state'"),
+            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_ENABLED => Ok(true),
+            ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1326,7 +1447,7 @@ impl Device {
     /// effect immediately.
     #[cfg(feature = "libinput_1_21")]
     pub fn config_dwtp_set_enabled(&self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_dwtp_set_enabled(
                 self.as_raw_mut(),
                 if enabled {
@@ -1335,16 +1456,8 @@ impl Device {
                     ffi::libinput_config_dwtp_state_LIBINPUT_CONFIG_DWTP_DISABLED
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::Dwtp(enabled), result)
     }
 
     ffi_func!(
@@ -1371,18 +1484,19 @@ impl Device {
     /// Changing the left-handed configuration of a device may not
     /// take effect until all buttons have been logically released.
     pub fn config_left_handed_set(&self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_left_handed_set(self.as_raw_mut(), enabled as i32)
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::LeftHanded(enabled), result)
+    }
+
+    /// Set the left-handed configuration of this device and all other
+    /// devices in its `DeviceGroup` supporting it.
+    ///
+    /// A tablet turned upside down for left-handed use turns its pad and
+    /// touch devices as well. See `DeviceGroup::config_left_handed_set`.
+    pub fn config_left_handed_set_group(&self, enabled: bool) -> Vec<(Device, DeviceConfigResult)> {
+        self.device_group().config_left_handed_set(enabled)
     }
 
     /// Check if configurable middle button emulation is enabled by
@@ -1398,13 +1512,13 @@ impl Device {
     /// Some devices provide middle mouse button emulation but do not
     /// allow enabling/disabling that emulation. These devices always
     /// return `false`.
-    pub fn config_middle_emulation_default_enabled(&self) -> bool {
+    pub fn config_middle_emulation_default_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe {
             ffi::libinput_device_config_middle_emulation_get_default_enabled(self.as_raw_mut())
         } {
-            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_ENABLED => true,
-            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_middle_emulation_state'"),
+            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_ENABLED => Ok(true),
+            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1422,11 +1536,11 @@ impl Device {
     /// Some devices provide middle mouse button emulation but do not
     /// allow enabling/disabling that emulation. These devices always
     /// return `false`.
-    pub fn config_middle_emulation_enabled(&self) -> bool {
+    pub fn config_middle_emulation_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_middle_emulation_get_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_ENABLED => true,
-            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_middle_emulation_state'"),
+            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_ENABLED => Ok(true),
+            ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1454,7 +1568,7 @@ impl Device {
     /// See [Middle button emulation](https://wayland.freedesktop.org/libinput/doc/latest/middle-button-emulation.html)
     /// for details.
     pub fn config_middle_emulation_set_enabled(&self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_middle_emulation_set_enabled(
                 self.as_raw_mut(),
                 if enabled {
@@ -1463,16 +1577,8 @@ impl Device {
                     ffi::libinput_config_middle_emulation_state_LIBINPUT_CONFIG_MIDDLE_EMULATION_DISABLED
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::MiddleEmulation(enabled), result)
     }
 
     ffi_func!(
@@ -1511,16 +1617,18 @@ impl Device {
     /// Setting a rotation of 0 degrees on a device that does not
     /// support rotation always succeeds.
     pub fn config_rotation_set_angle(&self, angle: u32) -> DeviceConfigResult {
-        match unsafe { ffi::libinput_device_config_rotation_set_angle(self.as_raw_mut(), angle) } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        let result = DeviceConfigError::from_ffi(unsafe {
+            ffi::libinput_device_config_rotation_set_angle(self.as_raw_mut(), angle)
+        });
+        self.remember(DeviceSetting::RotationAngle(angle), result)
+    }
+
+    /// Set the rotation of this device and all other devices in its
+    /// `DeviceGroup` supporting it.
+    ///
+    /// See `DeviceGroup::config_rotation_set_angle`.
+    pub fn config_rotation_set_angle_group(&self, angle: u32) -> Vec<(Device, DeviceConfigResult)> {
+        self.device_group().config_rotation_set_angle(angle)
     }
 
     ffi_func!(
@@ -1597,7 +1705,14 @@ impl Device {
             ffi::libinput_config_scroll_method_LIBINPUT_CONFIG_SCROLL_ON_BUTTON_DOWN => {
                 Some(ScrollMethod::OnButtonDown)
             }
-            _ => panic!("libinput returned invalid 'libinput_config_scroll_method'"),
+            _x => {
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "Unknown libinput_config_scroll_method ({}). Unsupported libinput version?",
+                    _x
+                );
+                None
+            }
         }
     }
 
@@ -1638,7 +1753,7 @@ impl Device {
     /// button is held down. If no button is set, i.e.
     /// `config_scroll_button` returns 0, scrolling cannot activate.
     pub fn config_scroll_set_method(&mut self, method: ScrollMethod) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_scroll_set_method(
                 self.as_raw_mut(),
                 match method {
@@ -1656,16 +1771,8 @@ impl Device {
                     }
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::ScrollMethod(method), result)
     }
 
     ffi_func!(
@@ -1700,21 +1807,13 @@ impl Device {
         &mut self,
         enabled: bool,
     ) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_scroll_set_natural_scroll_enabled(
                 self.as_raw_mut(),
                 enabled as i32,
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::NaturalScroll(enabled), result)
     }
 
     /// Set the button for the `ScrollMethod::OnButtonDown` method
@@ -1734,16 +1833,10 @@ impl Device {
     /// change the scroll method call `config_scroll_set_method`.
     /// If the button is 0, button scrolling is effectively disabled.
     pub fn config_scroll_set_button(&mut self, button: u32) -> DeviceConfigResult {
-        match unsafe { ffi::libinput_device_config_scroll_set_button(self.as_raw_mut(), button) } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        let result = DeviceConfigError::from_ffi(unsafe {
+            ffi::libinput_device_config_scroll_set_button(self.as_raw_mut(), button)
+        });
+        self.remember(DeviceSetting::ScrollButton(button), result)
     }
 
     /// Get the current scroll button lock state
@@ -1761,7 +1854,7 @@ impl Device {
         match unsafe { ffi::libinput_device_config_scroll_get_button_lock(self.as_raw() as *mut _) } {
             ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_DISABLED => ScrollButtonLockState::Disabled,
             ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_ENABLED => ScrollButtonLockState::Enabled,
-            _ => panic!("libinput returned invalid libinput_config_scroll_button_lock_state"),
+            x => ScrollButtonLockState::Unknown(x as u32),
         }
     }
 
@@ -1774,7 +1867,7 @@ impl Device {
         match unsafe { ffi::libinput_device_config_scroll_get_default_button_lock(self.as_raw() as *mut _) } {
             ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_DISABLED => ScrollButtonLockState::Disabled,
             ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_ENABLED => ScrollButtonLockState::Enabled,
-            _ => panic!("libinput returned invalid libinput_config_scroll_button_lock_state"),
+            x => ScrollButtonLockState::Unknown(x as u32),
         }
     }
 
@@ -1789,23 +1882,16 @@ impl Device {
         &mut self,
         state: ScrollButtonLockState,
     ) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_scroll_set_button_lock(self.as_raw_mut(),
             match state {
                 ScrollButtonLockState::Enabled => ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_ENABLED,
                 ScrollButtonLockState::Disabled => ffi::libinput_config_scroll_button_lock_state_LIBINPUT_CONFIG_SCROLL_BUTTON_LOCK_DISABLED,
+                ScrollButtonLockState::Unknown(x) => x as ffi::libinput_config_scroll_button_lock_state,
             }
         )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::ScrollButtonLock(state), result)
     }
 
     /// Get the send-event mode for this device.
@@ -1824,6 +1910,16 @@ impl Device {
         })
     }
 
+    /// Get the default send-event mode for this device.
+    ///
+    /// The mode defines when the device processes and sends events
+    /// to the caller.
+    pub fn config_send_events_default_mode(&self) -> SendEventsMode {
+        SendEventsMode::from_bits_truncate(unsafe {
+            ffi::libinput_device_config_send_events_get_default_mode(self.as_raw_mut())
+        })
+    }
+
     /// Return the possible send-event modes for this device.
     ///
     /// These modes define when a device may process and send events.
@@ -1852,18 +1948,10 @@ impl Device {
     /// nothing and returns success. Changing the send-event mode on
     /// a device that has been removed is permitted.
     pub fn config_send_events_set_mode(&self, mode: SendEventsMode) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_send_events_set_mode(self.as_raw_mut(), mode.bits())
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::SendEventsMode(mode), result)
     }
 
     /// Get the finger number to button number mapping for
@@ -1885,7 +1973,14 @@ impl Device {
                 ffi::libinput_config_tap_button_map_LIBINPUT_CONFIG_TAP_MAP_LMR => {
                     Some(TapButtonMap::LeftMiddleRight)
                 }
-                _ => panic!("libinput returned invalid 'libinput_config_tap_button_map'"),
+                _x => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Unknown libinput_config_tap_button_map ({}). Unsupported libinput version?",
+                        _x
+                    );
+                    None
+                }
             }
         }
     }
@@ -1911,19 +2006,26 @@ impl Device {
                 ffi::libinput_config_tap_button_map_LIBINPUT_CONFIG_TAP_MAP_LMR => {
                     Some(TapButtonMap::LeftMiddleRight)
                 }
-                _ => panic!("libinput returned invalid 'libinput_config_tap_button_map'"),
+                _x => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Unknown libinput_config_tap_button_map ({}). Unsupported libinput version?",
+                        _x
+                    );
+                    None
+                }
             }
         }
     }
 
     /// Return whether tap-and-drag is enabled or disabled by default
     /// on this device.
-    pub fn config_tap_default_drag_enabled(&self) -> bool {
+    pub fn config_tap_default_drag_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_tap_get_default_drag_enabled(self.as_raw_mut()) }
         {
-            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_ENABLED => true,
-            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_drag_state'"),
+            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_ENABLED => Ok(true),
+            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1955,27 +2057,27 @@ impl Device {
             ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED_STICKY => {
                 DragLockState::EnabledSticky
             }
-            _ => panic!("libinput returned invalid 'libinput_config_drag_lock_state'"),
+            x => DragLockState::Unknown(x as u32),
         }
     }
 
     /// Return the default setting for whether tap-to-click is
     /// enabled on this device.
-    pub fn config_tap_default_enabled(&self) -> bool {
+    pub fn config_tap_default_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_tap_get_default_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_ENABLED => true,
-            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_tap_state'"),
+            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_ENABLED => Ok(true),
+            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
     /// Return whether tap-and-drag is enabled or disabled on this
     /// device.
-    pub fn config_tap_drag_enabled(&self) -> bool {
+    pub fn config_tap_drag_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_tap_get_drag_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_ENABLED => true,
-            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_drag_state'"),
+            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_ENABLED => Ok(true),
+            ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -1985,11 +2087,36 @@ impl Device {
     /// returns `false`.
     ///
     /// Drag lock may be enabled even when tapping is disabled.
-    pub fn config_tap_drag_lock_enabled(&self) -> bool {
+    pub fn config_tap_drag_lock_enabled(&self) -> Result<bool, DeviceConfigError> {
+        match unsafe { ffi::libinput_device_config_tap_get_drag_lock_enabled(self.as_raw_mut()) } {
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED => Ok(true),
+            #[cfg(feature = "libinput_1_27")]
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED_STICKY => {
+                Ok(true)
+            }
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
+        }
+    }
+
+    /// Get the drag-lock state during tapping on this device.
+    ///
+    /// Unlike `config_tap_drag_lock_enabled`, this distinguishes the
+    /// drag lock modes. If the device does not support tapping, this
+    /// function always returns `DragLockState::Disabled`.
+    pub fn config_tap_drag_lock_state(&self) -> DragLockState {
         match unsafe { ffi::libinput_device_config_tap_get_drag_lock_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED => true,
-            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_drag_lock_state'"),
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_DISABLED => {
+                DragLockState::Disabled
+            }
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED => {
+                DragLockState::EnabledTimeout
+            }
+            #[cfg(feature = "libinput_1_27")]
+            ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED_STICKY => {
+                DragLockState::EnabledSticky
+            }
+            x => DragLockState::Unknown(x as u32),
         }
     }
 
@@ -1997,11 +2124,11 @@ impl Device {
     ///
     /// If the device does not support tapping, this function always
     /// returns `false`.
-    pub fn config_tap_enabled(&self) -> bool {
+    pub fn config_tap_enabled(&self) -> Result<bool, DeviceConfigError> {
         match unsafe { ffi::libinput_device_config_tap_get_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_ENABLED => true,
-            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_DISABLED => false,
-            _ => panic!("libinput returned invalid 'libinput_config_tap_state'"),
+            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_ENABLED => Ok(true),
+            ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_DISABLED => Ok(false),
+            x => Err(DeviceConfigError::UnknownState(x as u32)),
         }
     }
 
@@ -2012,10 +2139,21 @@ impl Device {
     /// See `config_tap_set_enabled` for more information.
     pub fn config_tap_finger_count, ffi::libinput_device_config_tap_get_finger_count, u32);
 
-    #[cfg(feature = "libinput_1_28")]
-    ffi_func!(
     /// Returns the maximum number of fingers available for 3-finger dragging.
-    pub fn config_3fg_drag_get_finger_count, ffi::libinput_device_config_3fg_drag_get_finger_count, u32);
+    ///
+    /// With the `dlopen` feature, this returns
+    /// [`DeviceConfigError::UnsupportedVersion`] if the libinput library in use
+    /// does not support 3-finger dragging.
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+    pub fn config_3fg_drag_get_finger_count(&self) -> Result<u32, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_device_config_3fg_drag_get_finger_count(
+                self.as_raw_mut()
+            ))
+        }
+        .map(|count| count as u32)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
+    }
 
     /// Enable or disable 3-finger drag on this device.
     ///
@@ -2024,68 +2162,62 @@ impl Device {
     /// after all fingers are logically up.
     ///
     /// See [Three-finger drag](https://wayland.freedesktop.org/libinput/doc/latest/configuration.html#three-finger-drag) for details.
-    #[cfg(feature = "libinput_1_28")]
+    ///
+    /// With the `dlopen` feature, this returns
+    /// [`DeviceConfigError::UnsupportedVersion`] if the libinput library in use
+    /// does not support 3-finger dragging, as do the getters.
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
     pub fn config_3fg_drag_set_enabled(&self, state: ThreeFingerDragState) -> DeviceConfigResult {
-        match unsafe {
-            ffi::libinput_device_config_3fg_drag_set_enabled(
-                self.as_raw_mut(),
-                match state {
-                    ThreeFingerDragState::Disabled => {
-                        ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED
-                    }
-                    ThreeFingerDragState::EnabledThreeFinger => {
-                        ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG
-                    }
-                    ThreeFingerDragState::EnabledFourFinger => {
-                        ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG
-                    }
-                },
-            )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
+        let setting = DeviceSetting::ThreeFingerDrag(state);
+        let state = match state {
+            ThreeFingerDragState::Disabled => {
+                ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED
             }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
+            ThreeFingerDragState::EnabledThreeFinger => {
+                ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG
             }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
+            ThreeFingerDragState::EnabledFourFinger => {
+                ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG
+            }
+            ThreeFingerDragState::Unknown(x) => x as ffi::libinput_config_3fg_drag_state,
+        };
+        let result = unsafe {
+            ffi_dyn!(libinput_device_config_3fg_drag_set_enabled(
+                self.as_raw_mut(),
+                state
+            ))
         }
+        .map_or(
+            Err(DeviceConfigError::UnsupportedVersion),
+            DeviceConfigError::from_ffi,
+        );
+        self.remember(setting, result)
     }
 
     /// Return whether 3-finger drag is enabled or disabled on this device.
-    #[cfg(feature = "libinput_1_28")]
-    pub fn config_3fg_drag_get_enabled(&self) -> ThreeFingerDragState {
-        match unsafe { ffi::libinput_device_config_3fg_drag_get_enabled(self.as_raw_mut()) } {
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED => {
-                ThreeFingerDragState::Disabled
-            }
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG => {
-                ThreeFingerDragState::EnabledThreeFinger
-            }
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG => {
-                ThreeFingerDragState::EnabledFourFinger
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_3fg_drag_state'"),
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+    pub fn config_3fg_drag_get_enabled(&self) -> Result<ThreeFingerDragState, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_device_config_3fg_drag_get_enabled(
+                self.as_raw_mut()
+            ))
         }
+        .map(ThreeFingerDragState::from_ffi)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
     /// Return whether 3-finger drag is enabled or disabled by default on this device.
-    #[cfg(feature = "libinput_1_28")]
-    pub fn config_3fg_drag_get_default_enabled(&self) -> ThreeFingerDragState {
-        match unsafe { ffi::libinput_device_config_3fg_drag_get_default_enabled(self.as_raw_mut()) }
-        {
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_DISABLED => {
-                ThreeFingerDragState::Disabled
-            }
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_3FG => {
-                ThreeFingerDragState::EnabledThreeFinger
-            }
-            ffi::libinput_config_3fg_drag_state_LIBINPUT_CONFIG_3FG_DRAG_ENABLED_4FG => {
-                ThreeFingerDragState::EnabledFourFinger
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_3fg_drag_state'"),
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+    pub fn config_3fg_drag_get_default_enabled(
+        &self,
+    ) -> Result<ThreeFingerDragState, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_device_config_3fg_drag_get_default_enabled(
+                self.as_raw_mut()
+            ))
         }
+        .map(ThreeFingerDragState::from_ffi)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
     /// Set the finger number to button number mapping for
@@ -2111,7 +2243,7 @@ impl Device {
     /// This will return `None` for devices where
     /// `config_tap_finger_count` returns 0.
     pub fn config_tap_set_button_map(&mut self, map: TapButtonMap) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_tap_set_button_map(
                 self.as_raw_mut(),
                 match map {
@@ -2123,16 +2255,8 @@ impl Device {
                     }
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::TapButtonMap(map), result)
     }
 
     /// Enable or disable tap-and-drag on this device.
@@ -2144,7 +2268,7 @@ impl Device {
     /// See [Tap-and-drag](https://wayland.freedesktop.org/libinput/doc/latest/tapping.html#tapndrag)
     /// for more details.
     pub fn config_tap_set_drag_enabled(&mut self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_tap_set_drag_enabled(
                 self.as_raw_mut(),
                 if enabled {
@@ -2153,16 +2277,8 @@ impl Device {
                     ffi::libinput_config_drag_state_LIBINPUT_CONFIG_DRAG_DISABLED
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::TapDrag(enabled), result)
     }
 
     /// Enable or disable drag-lock during tapping on this device.
@@ -2177,7 +2293,7 @@ impl Device {
     /// Enabling drag lock on a device that has tapping disabled is
     /// permitted, but has no effect until tapping is enabled.
     pub fn config_tap_set_drag_lock_enabled(&mut self, state: DragLockState) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_tap_set_drag_lock_enabled(
                 self.as_raw_mut(),
                 match state {
@@ -2197,18 +2313,11 @@ impl Device {
                     DragLockState::EnabledSticky => {
                         ffi::libinput_config_drag_lock_state_LIBINPUT_CONFIG_DRAG_LOCK_ENABLED_STICKY
                     }
+                    DragLockState::Unknown(x) => x as ffi::libinput_config_drag_lock_state,
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::TapDragLock(state), result)
     }
 
     /// Enable or disable tap-to-click on this device, with a default
@@ -2218,7 +2327,7 @@ impl Device {
     /// Tapping is limited by the number of simultaneous touches
     /// supported by the device, see `config_tap_finger_count`.
     pub fn config_tap_set_enabled(&mut self, enabled: bool) -> DeviceConfigResult {
-        match unsafe {
+        let result = DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_device_config_tap_set_enabled(
                 self.as_raw_mut(),
                 if enabled {
@@ -2227,15 +2336,7 @@ impl Device {
                     ffi::libinput_config_tap_state_LIBINPUT_CONFIG_TAP_DISABLED
                 },
             )
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        });
+        self.remember(DeviceSetting::Tap(enabled), result)
     }
 }
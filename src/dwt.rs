@@ -0,0 +1,375 @@
+#[cfg(feature = "libinput_1_19")]
+use crate::event::pointer::PointerScrollEvent;
+use crate::{
+    event::{
+        keyboard::{KeyState, KeyboardEventTrait},
+        pointer::{Axis, ButtonState, PointerEventTrait},
+        DeviceEvent, EventTrait, KeyboardEvent, PointerEvent,
+    },
+    Device, Event,
+};
+use std::time::Duration;
+
+/// Modifier keys from `linux/input-event-codes.h`
+const MODIFIERS: [u32; 9] = [
+    29,    // KEY_LEFTCTRL
+    42,    // KEY_LEFTSHIFT
+    54,    // KEY_RIGHTSHIFT
+    56,    // KEY_LEFTALT
+    97,    // KEY_RIGHTCTRL
+    100,   // KEY_RIGHTALT
+    125,   // KEY_LEFTMETA
+    126,   // KEY_RIGHTMETA
+    0x1d0, // KEY_FN
+];
+
+const AXES: [Axis; 2] = [Axis::Vertical, Axis::Horizontal];
+
+/// Whether a scroll event ends a scroll sequence, i.e. has a value of 0 on
+/// all of its axes.
+#[cfg(feature = "libinput_1_19")]
+fn is_scroll_stop<E: PointerScrollEvent>(event: &E) -> bool {
+    AXES.iter()
+        .copied()
+        .filter(|axis| event.has_axis(*axis))
+        .all(|axis| event.scroll_value(axis) == 0.0)
+}
+
+/// Configuration of a [`DwtFilter`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DwtConfig {
+    /// How long touchpad events are suppressed after a key press
+    pub timeout: Duration,
+    /// How long touchpad events are suppressed after a key press while
+    /// they are already suppressed, i.e. while typing
+    pub typing_timeout: Duration,
+    /// Keys that do not suppress touchpad events. Other keys do not
+    /// either while one of these is held, so shortcuts like ctrl+click
+    /// keep working.
+    pub modifiers: Vec<u32>,
+    /// Whether pointer motion is suppressed
+    pub suppress_motion: bool,
+    /// Whether scroll events are suppressed
+    pub suppress_scroll: bool,
+    /// Whether button presses, including taps, are suppressed
+    pub suppress_buttons: bool,
+}
+
+impl Default for DwtConfig {
+    /// The timeouts used by libinput, 200ms and 500ms, suppressing all
+    /// touchpad events and treating ctrl, shift, alt, meta and fn as
+    /// modifiers.
+    fn default() -> Self {
+        DwtConfig {
+            timeout: Duration::from_millis(200),
+            typing_timeout: Duration::from_millis(500),
+            modifiers: MODIFIERS.to_vec(),
+            suppress_motion: true,
+            suppress_scroll: true,
+            suppress_buttons: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Touchpad {
+    device: Device,
+    /// Keyboards suppressing the touchpad, `None` for all keyboards
+    keyboards: Option<Vec<Device>>,
+    /// Time in microseconds until which events are suppressed
+    until: Option<u64>,
+    /// Buttons whose press was suppressed
+    suppressed_buttons: Vec<u32>,
+    /// Whether the current finger or continuous scroll sequence is
+    /// suppressed, `None` outside of scroll sequences
+    scroll: Option<bool>,
+}
+
+impl Touchpad {
+    fn is_suppressed(&self, time_usec: u64) -> bool {
+        self.until.map_or(false, |until| time_usec < until)
+    }
+
+    /// Decide if an event of a finger or continuous scroll sequence is
+    /// delivered.
+    ///
+    /// Sequences are suppressed as a whole if they started while the
+    /// touchpad was suppressed. The stop events ending them are always
+    /// delivered, so scrolling never gets stuck.
+    fn scroll(&mut self, suppress: bool, stop: bool, time_usec: u64) -> bool {
+        if stop {
+            self.scroll = None;
+            return true;
+        }
+        let suppressed = match self.scroll {
+            Some(suppressed) => suppressed,
+            None => suppress && self.is_suppressed(time_usec),
+        };
+        self.scroll = Some(suppressed);
+        !suppressed
+    }
+}
+
+/// Disable-while-typing for arbitrary keyboard and touchpad pairs.
+///
+/// libinput only disables internal touchpads while typing on an internal
+/// keyboard, see `Device::config_dwt_set_enabled`. This filter suppresses
+/// the pointer, scroll and button events, including taps, of the selected
+/// touchpads for a short time after a key press on any keyboard, or on the
+/// keyboards they are paired with.
+///
+/// The filter works on the event stream: every event is passed to
+/// [`DwtFilter::filter`] and dropped if it returns `false`. Unlike
+/// inhibiting the touchpad, this leaves its configuration untouched.
+/// Button releases are only dropped if the matching press was, so buttons
+/// never get stuck. Likewise, finger and continuous scroll sequences are
+/// only dropped if they started while suppressed, and the events ending
+/// them are always delivered.
+///
+/// ```no_run
+/// # use input::{Device, DwtConfig, DwtFilter, Libinput};
+/// # fn run(mut context: Libinput, keyboard: &Device, touchpad: &Device) {
+/// let mut dwt = DwtFilter::new(DwtConfig::default());
+/// dwt.pair(keyboard, touchpad);
+/// loop {
+///     context.dispatch().unwrap();
+///     for event in &mut context {
+///         if dwt.filter(&event) {
+///             // handle event
+///         }
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DwtFilter {
+    config: DwtConfig,
+    touchpads: Vec<Touchpad>,
+    /// Modifiers currently held per keyboard
+    held: Vec<(Device, u32)>,
+}
+
+impl DwtFilter {
+    /// Create a filter without any touchpads.
+    pub fn new(config: DwtConfig) -> DwtFilter {
+        DwtFilter {
+            config,
+            touchpads: Vec::new(),
+            held: Vec::new(),
+        }
+    }
+
+    /// The configuration of the filter.
+    pub fn config(&self) -> &DwtConfig {
+        &self.config
+    }
+
+    /// Replace the configuration of the filter.
+    ///
+    /// Active suppressions keep their timeout.
+    pub fn set_config(&mut self, config: DwtConfig) {
+        self.config = config;
+    }
+
+    fn touchpad_mut(&mut self, touchpad: &Device) -> &mut Touchpad {
+        let index = match self
+            .touchpads
+            .iter()
+            .position(|entry| entry.device == *touchpad)
+        {
+            Some(index) => index,
+            None => {
+                self.touchpads.push(Touchpad {
+                    device: touchpad.clone(),
+                    keyboards: Some(Vec::new()),
+                    until: None,
+                    suppressed_buttons: Vec::new(),
+                    scroll: None,
+                });
+                self.touchpads.len() - 1
+            }
+        };
+        &mut self.touchpads[index]
+    }
+
+    /// Suppress events of a touchpad while typing on any keyboard.
+    ///
+    /// Overrides previous pairings of the touchpad.
+    pub fn add_touchpad(&mut self, touchpad: &Device) {
+        self.touchpad_mut(touchpad).keyboards = None;
+    }
+
+    /// Suppress events of a touchpad while typing on the given keyboard.
+    ///
+    /// A touchpad may be paired with multiple keyboards. Has no effect if
+    /// the touchpad was added with `add_touchpad`.
+    pub fn pair(&mut self, keyboard: &Device, touchpad: &Device) {
+        if let Some(keyboards) = &mut self.touchpad_mut(touchpad).keyboards {
+            if !keyboards.contains(keyboard) {
+                keyboards.push(keyboard.clone());
+            }
+        }
+    }
+
+    /// Stop suppressing events of a touchpad.
+    pub fn remove_touchpad(&mut self, touchpad: &Device) {
+        self.touchpads.retain(|entry| entry.device != *touchpad);
+    }
+
+    /// The touchpads handled by the filter.
+    pub fn touchpads(&self) -> Vec<Device> {
+        self.touchpads
+            .iter()
+            .map(|entry| entry.device.clone())
+            .collect()
+    }
+
+    /// Whether events of a touchpad are suppressed at the given time in
+    /// microseconds, in the clock of the event timestamps.
+    pub fn is_suppressed(&self, touchpad: &Device, time_usec: u64) -> bool {
+        self.touchpads
+            .iter()
+            .find(|entry| entry.device == *touchpad)
+            .map_or(false, |entry| entry.is_suppressed(time_usec))
+    }
+
+    /// Update the filter from an event and decide if it is delivered.
+    ///
+    /// Returns `false` if the event is suppressed and should be dropped.
+    /// Removed devices are forgotten by the filter.
+    pub fn filter(&mut self, event: &Event) -> bool {
+        match event {
+            Event::Keyboard(KeyboardEvent::Key(event)) => {
+                self.key(
+                    &event.device(),
+                    event.key(),
+                    event.key_state(),
+                    event.time_usec(),
+                );
+                true
+            }
+            Event::Pointer(event) => self.pointer(event),
+            Event::Device(DeviceEvent::Removed(event)) => {
+                let device = event.device();
+                self.touchpads.retain(|entry| entry.device != device);
+                for entry in &mut self.touchpads {
+                    if let Some(keyboards) = &mut entry.keyboards {
+                        keyboards.retain(|keyboard| *keyboard != device);
+                    }
+                }
+                self.held.retain(|(keyboard, _)| *keyboard != device);
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn key(&mut self, keyboard: &Device, key: u32, state: KeyState, time_usec: u64) {
+        if self.config.modifiers.contains(&key) {
+            match state {
+                KeyState::Pressed => self.held.push((keyboard.clone(), key)),
+                _ => self
+                    .held
+                    .retain(|(device, held)| !(device == keyboard && *held == key)),
+            }
+            return;
+        }
+        if state != KeyState::Pressed || self.held.iter().any(|(device, _)| device == keyboard) {
+            return;
+        }
+        let config = &self.config;
+        for entry in &mut self.touchpads {
+            let paired = entry
+                .keyboards
+                .as_ref()
+                .map_or(true, |keyboards| keyboards.contains(keyboard));
+            if !paired {
+                continue;
+            }
+            let timeout = if entry.is_suppressed(time_usec) {
+                config.typing_timeout
+            } else {
+                config.timeout
+            };
+            let until = time_usec + timeout.as_micros() as u64;
+            entry.until = Some(entry.until.map_or(until, |previous| previous.max(until)));
+        }
+    }
+
+    fn pointer(&mut self, event: &PointerEvent) -> bool {
+        let device = event.device();
+        let config = &self.config;
+        let entry = match self
+            .touchpads
+            .iter_mut()
+            .find(|entry| entry.device == device)
+        {
+            Some(entry) => entry,
+            None => return true,
+        };
+        match event {
+            PointerEvent::Motion(event) => {
+                !(config.suppress_motion && entry.is_suppressed(event.time_usec()))
+            }
+            PointerEvent::MotionAbsolute(event) => {
+                !(config.suppress_motion && entry.is_suppressed(event.time_usec()))
+            }
+            PointerEvent::Button(event) => match event.button_state() {
+                ButtonState::Pressed => {
+                    if config.suppress_buttons && entry.is_suppressed(event.time_usec()) {
+                        entry.suppressed_buttons.push(event.button());
+                        false
+                    } else {
+                        true
+                    }
+                }
+                _ => {
+                    let button = event.button();
+                    match entry
+                        .suppressed_buttons
+                        .iter()
+                        .position(|other| *other == button)
+                    {
+                        Some(index) => {
+                            entry.suppressed_buttons.remove(index);
+                            false
+                        }
+                        None => true,
+                    }
+                }
+            },
+            #[allow(deprecated)]
+            PointerEvent::Axis(event) => {
+                use crate::event::pointer::AxisSource;
+                match event.axis_source() {
+                    AxisSource::Finger | AxisSource::Continuous => {
+                        let stop = AXES
+                            .iter()
+                            .copied()
+                            .filter(|axis| event.has_axis(*axis))
+                            .all(|axis| event.axis_value(axis) == 0.0);
+                        entry.scroll(config.suppress_scroll, stop, event.time_usec())
+                    }
+                    _ => !(config.suppress_scroll && entry.is_suppressed(event.time_usec())),
+                }
+            }
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollWheel(event) => {
+                !(config.suppress_scroll && entry.is_suppressed(event.time_usec()))
+            }
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollFinger(event) => entry.scroll(
+                config.suppress_scroll,
+                is_scroll_stop(event),
+                event.time_usec(),
+            ),
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollContinuous(event) => entry.scroll(
+                config.suppress_scroll,
+                is_scroll_stop(event),
+                event.time_usec(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::DeviceCapability;
+use std::{error::Error as StdError, ffi::NulError, fmt, io::Error as IoError, path::PathBuf};
+
+/// Errors returned by operations on a [`Libinput`](crate::Libinput) context
+/// or its devices.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// libinput failed to create a new context.
+    ContextCreation,
+    /// libinput failed to assign a seat to a udev context, e.g. because a
+    /// seat was already assigned.
+    SeatAssignment,
+    /// libinput failed to resume a suspended context.
+    Resume,
+    /// libinput failed to move a device to a new logical seat.
+    SeatChange,
+    /// The device lacks the capability required by the operation.
+    MissingCapability(DeviceCapability),
+    /// A string argument contained a null-byte.
+    Nul(NulError),
+    /// An operating system error.
+    Io(IoError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ContextCreation => f.write_str("Failed to create libinput context"),
+            Error::SeatAssignment => f.write_str("Failed to assign seat"),
+            Error::Resume => f.write_str("Failed to resume libinput context"),
+            Error::SeatChange => f.write_str("Failed to change the logical seat of the device"),
+            Error::MissingCapability(cap) => {
+                write!(f, "Device is missing the {:?} capability", cap)
+            }
+            Error::Nul(err) => write!(f, "Invalid string argument: {}", err),
+            Error::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Nul(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(err: NulError) -> Self {
+        Error::Nul(err)
+    }
+}
+
+impl From<IoError> for Error {
+    fn from(err: IoError) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// A device libinput failed to open, see
+/// [`Libinput::open_failures`](crate::Libinput::open_failures).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct OpenFailure {
+    /// The path of the device
+    pub path: PathBuf,
+    /// The positive errno returned by the `LibinputInterface`
+    pub errno: i32,
+    /// Flags the device was opened with as defined by open(2)
+    pub flags: i32,
+    /// Whether the device failed to open in `Libinput::path_add_device`.
+    ///
+    /// libinput drops such devices, they have to be added again. Other
+    /// devices are only skipped, libinput opens them again once they are
+    /// re-added by udev or the context is resumed.
+    pub path_add: bool,
+}
+
+impl OpenFailure {
+    /// The errno as `std::io::Error`.
+    pub fn io_error(&self) -> IoError {
+        IoError::from_raw_os_error(self.errno)
+    }
+}
+
+impl fmt::Display for OpenFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to open {}: {}",
+            self.path.display(),
+            self.io_error()
+        )
+    }
+}
+
+impl StdError for OpenFailure {}
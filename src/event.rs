@@ -22,6 +22,9 @@ pub enum Event {
     Gesture(GestureEvent),
     /// A switch related `Event`
     Switch(SwitchEvent),
+    /// An `Event` of a type unknown to this library, e.g. because it was
+    /// introduced by a newer libinput version than the enabled features cover
+    Unknown(UnknownEvent),
 }
 
 /// Common functions all (Sub-)Events implement.
@@ -63,100 +66,94 @@ impl FromRaw<ffi::libinput_event> for Event {
     }
 
     unsafe fn try_from_raw(event: *mut ffi::libinput_event, context: &Libinput) -> Option<Self> {
+        // libinput logs a client bug if the sub-event getters are called on
+        // events of another interface, so only events of types known to this
+        // library are converted, all others are kept as `UnknownEvent`.
+        macro_rules! sub_event {
+            ($variant:ident, $event:ident, $get_fn:path) => {
+                Some(Event::$variant($event::try_from_raw(
+                    $get_fn(event),
+                    context,
+                )?))
+            };
+        }
+
         match ffi::libinput_event_get_type(event) {
             ffi::libinput_event_type_LIBINPUT_EVENT_NONE => None,
             ffi::libinput_event_type_LIBINPUT_EVENT_DEVICE_ADDED
-            | ffi::libinput_event_type_LIBINPUT_EVENT_DEVICE_REMOVED => {
-                Some(Event::Device(DeviceEvent::try_from_raw(
-                    ffi::libinput_event_get_device_notify_event(event),
-                    context,
-                )?))
-            }
-            ffi::libinput_event_type_LIBINPUT_EVENT_KEYBOARD_KEY => {
-                Some(Event::Keyboard(KeyboardEvent::try_from_raw(
-                    ffi::libinput_event_get_keyboard_event(event),
-                    context,
-                )?))
-            }
-            #[cfg(not(feature = "libinput_1_19"))]
+            | ffi::libinput_event_type_LIBINPUT_EVENT_DEVICE_REMOVED => sub_event!(
+                Device,
+                DeviceEvent,
+                ffi::libinput_event_get_device_notify_event
+            ),
+            ffi::libinput_event_type_LIBINPUT_EVENT_KEYBOARD_KEY => sub_event!(
+                Keyboard,
+                KeyboardEvent,
+                ffi::libinput_event_get_keyboard_event
+            ),
             ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_MOTION
             | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_MOTION_ABSOLUTE
             | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_BUTTON
-            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_AXIS => Some(Event::Pointer(
-                PointerEvent::try_from_raw(ffi::libinput_event_get_pointer_event(event), context)?,
-            )),
+            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_AXIS => {
+                sub_event!(Pointer, PointerEvent, ffi::libinput_event_get_pointer_event)
+            }
             #[cfg(feature = "libinput_1_19")]
-            ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_MOTION
-            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_MOTION_ABSOLUTE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_BUTTON
-            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_AXIS
-            | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_SCROLL_WHEEL
+            ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_SCROLL_WHEEL
             | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_SCROLL_FINGER
             | ffi::libinput_event_type_LIBINPUT_EVENT_POINTER_SCROLL_CONTINUOUS => {
-                Some(Event::Pointer(PointerEvent::try_from_raw(
-                    ffi::libinput_event_get_pointer_event(event),
-                    context,
-                )?))
+                sub_event!(Pointer, PointerEvent, ffi::libinput_event_get_pointer_event)
             }
             ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_DOWN
             | ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_UP
             | ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_MOTION
             | ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_CANCEL
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_FRAME => Some(Event::Touch(
-                TouchEvent::try_from_raw(ffi::libinput_event_get_touch_event(event), context)?,
-            )),
+            | ffi::libinput_event_type_LIBINPUT_EVENT_TOUCH_FRAME => {
+                sub_event!(Touch, TouchEvent, ffi::libinput_event_get_touch_event)
+            }
             ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_TOOL_AXIS
             | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_TOOL_PROXIMITY
             | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_TOOL_TIP
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_TOOL_BUTTON => {
-                Some(Event::Tablet(TabletToolEvent::try_from_raw(
-                    ffi::libinput_event_get_tablet_tool_event(event),
-                    context,
-                )?))
-            }
-            #[cfg(not(feature = "libinput_1_15"))]
+            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_TOOL_BUTTON => sub_event!(
+                Tablet,
+                TabletToolEvent,
+                ffi::libinput_event_get_tablet_tool_event
+            ),
             ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_BUTTON
             | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_RING
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_STRIP => {
-                Some(Event::TabletPad(TabletPadEvent::try_from_raw(
-                    ffi::libinput_event_get_tablet_pad_event(event),
-                    context,
-                )?))
-            }
+            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_STRIP => sub_event!(
+                TabletPad,
+                TabletPadEvent,
+                ffi::libinput_event_get_tablet_pad_event
+            ),
             #[cfg(feature = "libinput_1_15")]
-            ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_BUTTON
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_RING
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_STRIP
-            | ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_KEY => {
-                Some(Event::TabletPad(TabletPadEvent::try_from_raw(
-                    ffi::libinput_event_get_tablet_pad_event(event),
-                    context,
-                )?))
-            }
-            #[cfg(not(feature = "libinput_1_19"))]
+            ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_KEY => sub_event!(
+                TabletPad,
+                TabletPadEvent,
+                ffi::libinput_event_get_tablet_pad_event
+            ),
+            #[cfg(feature = "libinput_1_26")]
+            ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_DIAL => sub_event!(
+                TabletPad,
+                TabletPadEvent,
+                ffi::libinput_event_get_tablet_pad_event
+            ),
             ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_BEGIN
             | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_UPDATE
             | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_END
             | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_BEGIN
             | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_END => Some(Event::Gesture(
-                GestureEvent::try_from_raw(ffi::libinput_event_get_gesture_event(event), context)?,
-            )),
+            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_END => {
+                sub_event!(Gesture, GestureEvent, ffi::libinput_event_get_gesture_event)
+            }
             #[cfg(feature = "libinput_1_19")]
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_END
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_END
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_END => Some(Event::Gesture(
-                GestureEvent::try_from_raw(ffi::libinput_event_get_gesture_event(event), context)?,
-            )),
-            ffi::libinput_event_type_LIBINPUT_EVENT_SWITCH_TOGGLE => Some(Event::Switch(
-                SwitchEvent::try_from_raw(ffi::libinput_event_get_switch_event(event), context)?,
-            )),
-            _ => None,
+            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_BEGIN
+            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_END => {
+                sub_event!(Gesture, GestureEvent, ffi::libinput_event_get_gesture_event)
+            }
+            ffi::libinput_event_type_LIBINPUT_EVENT_SWITCH_TOGGLE => {
+                sub_event!(Switch, SwitchEvent, ffi::libinput_event_get_switch_event)
+            }
+            _ => Some(Event::Unknown(UnknownEvent::from_raw(event, context))),
         }
     }
 }
@@ -172,6 +169,7 @@ impl AsRaw<ffi::libinput_event> for Event {
             Event::TabletPad(event) => event.as_raw_event() as *const _,
             Event::Gesture(event) => event.as_raw_event() as *const _,
             Event::Switch(event) => event.as_raw_event() as *const _,
+            Event::Unknown(event) => event.as_raw(),
         }
     }
 }
@@ -187,6 +185,7 @@ impl Context for Event {
             Event::TabletPad(event) => event.context(),
             Event::Gesture(event) => event.context(),
             Event::Switch(event) => event.context(),
+            Event::Unknown(event) => event.context(),
         }
     }
 }
@@ -260,6 +259,29 @@ macro_rules! ffi_event_struct {
     )
 }
 
+unsafe fn base_event(event: *mut ffi::libinput_event) -> *mut ffi::libinput_event {
+    event
+}
+
+ffi_event_struct!(
+/// An event of a type unknown to this library.
+///
+/// Such events are emitted if the libinput version in use is newer than
+/// the enabled `libinput_1_*` features. They still carry the device and
+/// type of the event, so they can be accounted for or forwarded.
+///
+/// Unknown events are only returned as `Event::Unknown`, even if they
+/// belong to an interface known to this library, e.g. new pointer events,
+/// as libinput only converts events of the types it knows into the
+/// sub-event types.
+struct UnknownEvent, ffi::libinput_event, base_event);
+
+impl UnknownEvent {
+    ffi_func!(
+    /// Return the raw libinput event type of this event.
+    pub fn event_type, ffi::libinput_event_get_type, u32);
+}
+
 pub mod device;
 pub mod gesture;
 pub mod keyboard;
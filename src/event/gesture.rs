@@ -3,6 +3,9 @@
 use super::EventTrait;
 use crate::{ffi, AsRaw, Context, FromRaw, Libinput};
 
+mod tracker;
+pub use self::tracker::*;
+
 /// Common functions all Gesture-Events implement.
 pub trait GestureEventTrait: AsRaw<ffi::libinput_event_gesture> + Context {
     ffi_func!(
@@ -215,16 +218,6 @@ impl FromRaw<ffi::libinput_event_gesture> for GestureSwipeEvent {
             ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_END => Some(
                 GestureSwipeEvent::End(GestureSwipeEndEvent::try_from_raw(event, context)?),
             ),
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_END => {
-                panic!("Tried to make GestureSwipeEvent from Pinch event")
-            }
-            #[cfg(feature = "libinput_1_19")]
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_END => {
-                panic!("Tried to make GestureSwipeEvent from Hold event")
-            }
             _ => None,
         }
     }
@@ -312,16 +305,6 @@ impl FromRaw<ffi::libinput_event_gesture> for GesturePinchEvent {
     ) -> Option<Self> {
         let base = ffi::libinput_event_gesture_get_base_event(event);
         match ffi::libinput_event_get_type(base) {
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_END => {
-                panic!("Tried to make GesturePinchEvent from Swipe event")
-            }
-            #[cfg(feature = "libinput_1_19")]
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_END => {
-                panic!("Tried to make GestureSwipeEvent from Hold event")
-            }
             ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_BEGIN => Some(
                 GesturePinchEvent::Begin(GesturePinchBeginEvent::try_from_raw(event, context)?),
             ),
@@ -404,16 +387,6 @@ impl FromRaw<ffi::libinput_event_gesture> for GestureHoldEvent {
     ) -> Option<Self> {
         let base = ffi::libinput_event_gesture_get_base_event(event);
         match ffi::libinput_event_get_type(base) {
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_SWIPE_END => {
-                panic!("Tried to make GesturePinchEvent from Swipe event")
-            }
-            ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_BEGIN
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_UPDATE
-            | ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_PINCH_END => {
-                panic!("Tried to make GestureSwipeEvent from Pinch event")
-            }
             ffi::libinput_event_type_LIBINPUT_EVENT_GESTURE_HOLD_BEGIN => Some(
                 GestureHoldEvent::Begin(GestureHoldBeginEvent::try_from_raw(event, context)?),
             ),
@@ -0,0 +1,477 @@
+use super::{
+    GestureEndEvent, GestureEvent, GestureEventCoordinates, GestureEventTrait, GesturePinchEvent,
+    GesturePinchEventTrait, GestureSwipeEvent,
+};
+use crate::{event::EventTrait, Device};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+/// Kind of gesture tracked by `GestureTracker`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureKind {
+    /// A swipe gesture
+    Swipe,
+    /// A pinch gesture
+    Pinch,
+}
+
+/// Dominant direction of a finished gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GestureDirection {
+    /// Swipe towards the left
+    Left,
+    /// Swipe towards the right
+    Right,
+    /// Swipe upwards
+    Up,
+    /// Swipe downwards
+    Down,
+    /// Pinch with the fingers moving together
+    In,
+    /// Pinch with the fingers moving apart
+    Out,
+}
+
+/// Cumulative state of an ongoing gesture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GestureProgress {
+    /// The device performing the gesture
+    pub device: Device,
+    /// The kind of gesture
+    pub kind: GestureKind,
+    /// The number of fingers used for the gesture
+    pub fingers: i32,
+    /// Accumulated horizontal motion since the gesture began
+    pub dx: f64,
+    /// Accumulated vertical motion since the gesture began
+    pub dy: f64,
+    /// Horizontal swipe progress in workspaces, i.e. `dx` divided by the
+    /// configured distance per workspace
+    pub progress_x: f64,
+    /// Vertical swipe progress in workspaces, i.e. `dy` divided by the
+    /// configured distance per workspace
+    pub progress_y: f64,
+    /// Cumulative pinch scale, 1.0 for swipes
+    pub scale: f64,
+    /// Cumulative pinch rotation in degrees clockwise, 0.0 for swipes
+    pub rotation: f64,
+}
+
+/// Result of a finished gesture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GestureOutcome {
+    /// Final cumulative state of the gesture
+    pub progress: GestureProgress,
+    /// Dominant direction, `None` if the gesture did not move
+    pub direction: Option<GestureDirection>,
+    /// Velocity at the end of the gesture.
+    ///
+    /// For swipes this is the motion in units per millisecond along the
+    /// dominant axis, for pinches the change of scale per millisecond.
+    pub velocity: f64,
+    /// Whether libinput cancelled the gesture
+    pub cancelled: bool,
+    /// Whether the action associated with the gesture should be committed,
+    /// e.g. switching to the next workspace, or reverted.
+    ///
+    /// Cancelled gestures are never committed.
+    pub commit: bool,
+}
+
+/// Update reported by `GestureTracker::handle_event`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GestureUpdate {
+    /// A gesture began
+    Begin(GestureProgress),
+    /// A gesture progressed
+    Update(GestureProgress),
+    /// A gesture ended
+    End(GestureOutcome),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time_usec: u64,
+    dx: f64,
+    dy: f64,
+    scale: f64,
+}
+
+/// Motion of a gesture within the velocity window.
+#[derive(Debug)]
+struct Samples(VecDeque<Sample>);
+
+impl Samples {
+    fn new(time_usec: u64) -> Self {
+        let mut samples = VecDeque::new();
+        samples.push_back(Sample {
+            time_usec,
+            dx: 0.0,
+            dy: 0.0,
+            scale: 1.0,
+        });
+        Samples(samples)
+    }
+
+    /// Record a sample, dropping samples older than `window_usec` while
+    /// keeping at least two.
+    fn push(&mut self, window_usec: u64, sample: Sample) {
+        let time_usec = sample.time_usec;
+        self.0.push_back(sample);
+        while self.0.len() > 2
+            && self
+                .0
+                .front()
+                .map(|s| time_usec.saturating_sub(s.time_usec) > window_usec)
+                .unwrap_or(false)
+        {
+            self.0.pop_front();
+        }
+    }
+
+    /// Milliseconds from the oldest sample to `time_usec`.
+    fn elapsed_ms(&self, time_usec: u64) -> f64 {
+        self.0
+            .front()
+            .map(|first| time_usec.saturating_sub(first.time_usec) as f64 / 1000.0)
+            .unwrap_or(0.0)
+    }
+
+    /// Motion per millisecond along one axis up to `time_usec`.
+    fn velocity(&self, time_usec: u64, horizontal: bool) -> f64 {
+        let elapsed_ms = self.elapsed_ms(time_usec);
+        if elapsed_ms <= 0.0 {
+            return 0.0;
+        }
+        let travelled: f64 = self
+            .0
+            .iter()
+            .skip(1)
+            .map(|s| if horizontal { s.dx } else { s.dy })
+            .sum();
+        travelled / elapsed_ms
+    }
+
+    /// Change of scale per millisecond up to `time_usec`.
+    fn scale_velocity(&self, time_usec: u64) -> f64 {
+        let elapsed_ms = self.elapsed_ms(time_usec);
+        match (self.0.front(), self.0.back()) {
+            (Some(first), Some(last)) if elapsed_ms > 0.0 => {
+                (last.scale - first.scale) / elapsed_ms
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ActiveGesture {
+    progress: GestureProgress,
+    samples: Samples,
+}
+
+/// Tracks swipe and pinch gestures for animations such as workspace
+/// switching or overviews.
+///
+/// libinput only reports deltas between `Begin`, `Update` and `End`. The
+/// tracker accumulates them, normalizes swipe motion against a configurable
+/// distance per workspace and keeps the cumulative pinch scale and
+/// rotation. Once the gesture ends it decides whether the gesture should be
+/// committed based on the distance travelled and the velocity at release.
+#[derive(Debug)]
+pub struct GestureTracker {
+    swipe_distance: f64,
+    commit_progress: f64,
+    commit_velocity: f64,
+    commit_scale: f64,
+    commit_scale_velocity: f64,
+    velocity_window_usec: u64,
+    active: HashMap<Device, ActiveGesture>,
+}
+
+impl Default for GestureTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GestureTracker {
+    /// Create a new tracker.
+    ///
+    /// By default a workspace is 300 units wide, swipes commit after half
+    /// a workspace or when released faster than 0.5 units per millisecond,
+    /// and pinches commit after a scale change of 20% or when released
+    /// faster than 0.001 scale per millisecond. The release velocity is
+    /// measured over the last 100ms of the gesture.
+    pub fn new() -> Self {
+        GestureTracker {
+            swipe_distance: 300.0,
+            commit_progress: 0.5,
+            commit_velocity: 0.5,
+            commit_scale: 0.2,
+            commit_scale_velocity: 0.001,
+            velocity_window_usec: 100_000,
+            active: HashMap::new(),
+        }
+    }
+
+    /// Set the swipe distance that equals the progress of one workspace.
+    pub fn set_swipe_distance(&mut self, distance: f64) {
+        self.swipe_distance = distance;
+    }
+
+    /// Set the thresholds for committing swipes.
+    ///
+    /// ## Arguments
+    ///
+    /// - progress - Progress in workspaces along the dominant axis
+    /// - velocity - Release velocity in units per millisecond along the dominant axis
+    pub fn set_swipe_commit_threshold(&mut self, progress: f64, velocity: f64) {
+        self.commit_progress = progress;
+        self.commit_velocity = velocity;
+    }
+
+    /// Set the thresholds for committing pinches.
+    ///
+    /// ## Arguments
+    ///
+    /// - scale - Absolute change of the cumulative scale from 1.0
+    /// - velocity - Absolute change of scale per millisecond at release
+    pub fn set_pinch_commit_threshold(&mut self, scale: f64, velocity: f64) {
+        self.commit_scale = scale;
+        self.commit_scale_velocity = velocity;
+    }
+
+    /// Set the window of motion before the release that is used to
+    /// estimate the release velocity.
+    pub fn set_velocity_window(&mut self, window: Duration) {
+        self.velocity_window_usec = window.as_micros() as u64;
+    }
+
+    /// Return the progress of the ongoing gesture on the given device, if any.
+    pub fn progress(&self, device: &Device) -> Option<&GestureProgress> {
+        self.active.get(device).map(|active| &active.progress)
+    }
+
+    /// Process a gesture event.
+    ///
+    /// Returns `None` for hold gestures.
+    pub fn handle_event(&mut self, event: &GestureEvent) -> Option<GestureUpdate> {
+        match event {
+            GestureEvent::Swipe(GestureSwipeEvent::Begin(event)) => Some(self.begin(
+                event.device(),
+                GestureKind::Swipe,
+                event.finger_count(),
+                event.time_usec(),
+            )),
+            GestureEvent::Swipe(GestureSwipeEvent::Update(event)) => self.update(
+                &event.device(),
+                event.time_usec(),
+                event.dx(),
+                event.dy(),
+                None,
+                0.0,
+            ),
+            GestureEvent::Swipe(GestureSwipeEvent::End(event)) => {
+                self.end(&event.device(), event.time_usec(), event.cancelled())
+            }
+            GestureEvent::Pinch(GesturePinchEvent::Begin(event)) => Some(self.begin(
+                event.device(),
+                GestureKind::Pinch,
+                event.finger_count(),
+                event.time_usec(),
+            )),
+            GestureEvent::Pinch(GesturePinchEvent::Update(event)) => self.update(
+                &event.device(),
+                event.time_usec(),
+                event.dx(),
+                event.dy(),
+                Some(event.scale()),
+                event.angle_delta(),
+            ),
+            GestureEvent::Pinch(GesturePinchEvent::End(event)) => {
+                self.end(&event.device(), event.time_usec(), event.cancelled())
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    fn begin(
+        &mut self,
+        device: Device,
+        kind: GestureKind,
+        fingers: i32,
+        time_usec: u64,
+    ) -> GestureUpdate {
+        let progress = GestureProgress {
+            device: device.clone(),
+            kind,
+            fingers,
+            dx: 0.0,
+            dy: 0.0,
+            progress_x: 0.0,
+            progress_y: 0.0,
+            scale: 1.0,
+            rotation: 0.0,
+        };
+        self.active.insert(
+            device,
+            ActiveGesture {
+                progress: progress.clone(),
+                samples: Samples::new(time_usec),
+            },
+        );
+        GestureUpdate::Begin(progress)
+    }
+
+    fn update(
+        &mut self,
+        device: &Device,
+        time_usec: u64,
+        dx: f64,
+        dy: f64,
+        scale: Option<f64>,
+        angle_delta: f64,
+    ) -> Option<GestureUpdate> {
+        let swipe_distance = self.swipe_distance;
+        let window = self.velocity_window_usec;
+        let active = self.active.get_mut(device)?;
+        let progress = &mut active.progress;
+        progress.dx += dx;
+        progress.dy += dy;
+        progress.progress_x = progress.dx / swipe_distance;
+        progress.progress_y = progress.dy / swipe_distance;
+        if let Some(scale) = scale {
+            progress.scale = scale;
+        }
+        progress.rotation += angle_delta;
+
+        active.samples.push(
+            window,
+            Sample {
+                time_usec,
+                dx,
+                dy,
+                scale: progress.scale,
+            },
+        );
+        Some(GestureUpdate::Update(progress.clone()))
+    }
+
+    fn end(&mut self, device: &Device, time_usec: u64, cancelled: bool) -> Option<GestureUpdate> {
+        let active = self.active.remove(device)?;
+        let progress = active.progress;
+
+        let (direction, velocity, commit) = match progress.kind {
+            GestureKind::Swipe => {
+                let horizontal = progress.dx.abs() >= progress.dy.abs();
+                let direction = match (horizontal, progress.dx, progress.dy) {
+                    (_, dx, dy) if dx == 0.0 && dy == 0.0 => None,
+                    (true, dx, _) if dx < 0.0 => Some(GestureDirection::Left),
+                    (true, _, _) => Some(GestureDirection::Right),
+                    (false, _, dy) if dy < 0.0 => Some(GestureDirection::Up),
+                    (false, _, _) => Some(GestureDirection::Down),
+                };
+                let velocity = active.samples.velocity(time_usec, horizontal);
+                let distance = if horizontal {
+                    progress.progress_x
+                } else {
+                    progress.progress_y
+                };
+                // a fast flick against the direction travelled reverts the gesture
+                let commit = distance.abs() >= self.commit_progress && velocity * distance >= 0.0
+                    || velocity.abs() >= self.commit_velocity && velocity * distance > 0.0;
+                (direction, velocity, commit)
+            }
+            GestureKind::Pinch => {
+                let direction = if progress.scale < 1.0 {
+                    Some(GestureDirection::In)
+                } else if progress.scale > 1.0 {
+                    Some(GestureDirection::Out)
+                } else {
+                    None
+                };
+                let velocity = active.samples.scale_velocity(time_usec);
+                let change = progress.scale - 1.0;
+                let commit = (change.abs() >= self.commit_scale && velocity * change >= 0.0)
+                    || (velocity.abs() >= self.commit_scale_velocity && velocity * change > 0.0);
+                (direction, velocity, commit)
+            }
+        };
+
+        Some(GestureUpdate::End(GestureOutcome {
+            progress,
+            direction,
+            velocity,
+            cancelled,
+            commit: commit && !cancelled,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Sample, Samples};
+
+    fn swipe(samples: &mut Samples, window_usec: u64, time_usec: u64, dx: f64) {
+        samples.push(
+            window_usec,
+            Sample {
+                time_usec,
+                dx,
+                dy: 0.0,
+                scale: 1.0,
+            },
+        );
+    }
+
+    #[test]
+    fn velocity_over_window() {
+        let mut samples = Samples::new(0);
+        // slow for 100ms, then fast for 100ms
+        for i in 1..=20 {
+            let dx = if i <= 10 { 1.0 } else { 10.0 };
+            swipe(&mut samples, 100_000, i * 10_000, dx);
+        }
+        assert!((samples.velocity(200_000, true) - 1.0).abs() < 1e-9);
+        assert_eq!(samples.velocity(200_000, false), 0.0);
+    }
+
+    #[test]
+    fn velocity_over_longer_window() {
+        let mut samples = Samples::new(0);
+        for i in 1..=20 {
+            let dx = if i <= 10 { 1.0 } else { 10.0 };
+            swipe(&mut samples, 1_000_000, i * 10_000, dx);
+        }
+        assert!((samples.velocity(200_000, true) - 0.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn velocity_keeps_two_samples() {
+        let mut samples = Samples::new(0);
+        swipe(&mut samples, 100_000, 500_000, 50.0);
+        assert!((samples.velocity(500_000, true) - 0.1).abs() < 1e-9);
+        assert_eq!(Samples::new(0).velocity(0, true), 0.0);
+    }
+
+    #[test]
+    fn scale_velocity() {
+        let mut samples = Samples::new(0);
+        for (time_usec, scale) in [(50_000, 1.1), (100_000, 1.2)] {
+            samples.push(
+                100_000,
+                Sample {
+                    time_usec,
+                    dx: 0.0,
+                    dy: 0.0,
+                    scale,
+                },
+            );
+        }
+        assert!((samples.scale_velocity(100_000) - 0.002).abs() < 1e-9);
+    }
+}
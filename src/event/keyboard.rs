@@ -10,6 +10,8 @@ pub enum KeyState {
     Pressed,
     /// Key is released
     Released,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 /// Common functions for all Keyboard-Events implement.
@@ -29,7 +31,7 @@ pub trait KeyboardEventTrait: AsRaw<ffi::libinput_event_keyboard> + Context {
         match unsafe { ffi::libinput_event_keyboard_get_key_state(self.as_raw() as *mut _) } {
             ffi::libinput_key_state_LIBINPUT_KEY_STATE_PRESSED => KeyState::Pressed,
             ffi::libinput_key_state_LIBINPUT_KEY_STATE_RELEASED => KeyState::Released,
-            _ => panic!("libinput returned invalid 'libinput_key_state'"),
+            x => KeyState::Unknown(x),
         }
     }
 
@@ -4,6 +4,11 @@
 use super::EventTrait;
 use crate::{ffi, AsRaw, Context, FromRaw, Libinput};
 
+mod kinetic;
+mod scroll;
+pub use self::kinetic::*;
+pub use self::scroll::*;
+
 /// Common functions for all Pointer-Events implement.
 pub trait PointerEventTrait: AsRaw<ffi::libinput_event_pointer> + Context {
     ffi_func!(
@@ -247,6 +252,8 @@ pub enum ButtonState {
     Pressed,
     /// Button is released
     Released,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 ffi_event_struct!(
@@ -267,7 +274,7 @@ impl PointerButtonEvent {
         match unsafe { ffi::libinput_event_pointer_get_button_state(self.as_raw_mut()) } {
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_PRESSED => ButtonState::Pressed,
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_RELEASED => ButtonState::Released,
-            _ => panic!("libinput returned invalid 'libinput_button_state'"),
+            x => ButtonState::Unknown(x),
         }
     }
 }
@@ -293,6 +300,8 @@ pub enum AxisSource {
         deprecated = "No device has ever sent this source."
     )]
     WheelTilt,
+    /// A source unknown to this library
+    Unknown(u32),
 }
 
 /// Axes on a device with the pointer capability that are not  x or y coordinates.
@@ -387,7 +396,7 @@ impl PointerAxisEvent {
                 AxisSource::WheelTilt
             }
             // Axis Event is deprecated, no new variants will be added
-            _ => unreachable!(),
+            x => AxisSource::Unknown(x),
         }
     }
 
@@ -0,0 +1,586 @@
+#[cfg(not(feature = "libinput_1_19"))]
+use super::AxisSource;
+#[cfg(feature = "libinput_1_19")]
+use super::PointerScrollEvent;
+use super::{Axis, PointerEvent, PointerEventTrait};
+#[cfg(feature = "libinput_1_19")]
+use crate::event::gesture::{GestureEvent, GestureHoldEvent};
+use crate::{
+    event::{Event, EventTrait},
+    Device,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{Error as IoError, Result as IoResult},
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    time::Duration,
+};
+
+/// Deceleration curve used by `KineticScroll` after the fingers were lifted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecelerationCurve {
+    /// The velocity decays exponentially, reaching 1/e of its value after the
+    /// given time constant.
+    Exponential {
+        /// Time constant in milliseconds
+        time_constant_ms: f64,
+    },
+    /// The velocity decreases linearly.
+    Linear {
+        /// Deceleration in scroll units per millisecond²
+        deceleration: f64,
+    },
+}
+
+impl DecelerationCurve {
+    /// Velocity after `t` milliseconds of coasting with initial velocity `v0`.
+    fn velocity(&self, v0: f64, t: f64) -> f64 {
+        match *self {
+            DecelerationCurve::Exponential { time_constant_ms } => {
+                v0 * (-t / time_constant_ms).exp()
+            }
+            DecelerationCurve::Linear { deceleration } => {
+                let v = v0.abs() - deceleration * t;
+                if v > 0.0 {
+                    v.copysign(v0)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Distance covered after `t` milliseconds of coasting with initial
+    /// velocity `v0`.
+    fn distance(&self, v0: f64, t: f64) -> f64 {
+        match *self {
+            DecelerationCurve::Exponential { time_constant_ms } => {
+                v0 * time_constant_ms * (1.0 - (-t / time_constant_ms).exp())
+            }
+            DecelerationCurve::Linear { deceleration } => {
+                let t = t.min(v0.abs() / deceleration);
+                (v0.abs() * t - 0.5 * deceleration * t * t).copysign(v0)
+            }
+        }
+    }
+}
+
+/// Scroll deltas of a single kinetic step produced by `KineticScroll`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KineticScrollFrame {
+    /// The device whose scroll sequence is continued
+    pub device: Device,
+    /// Time of this step in microseconds (`CLOCK_MONOTONIC`)
+    pub time_usec: u64,
+    /// Horizontal scroll delta since the last step
+    pub dx: f64,
+    /// Vertical scroll delta since the last step
+    pub dy: f64,
+    /// Whether this is the last step of the coast.
+    ///
+    /// Like finger scroll sequences, the last step can be used to send an
+    /// axis stop to clients.
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time_usec: u64,
+    dx: f64,
+    dy: f64,
+}
+
+/// Recent deltas of a motion, used to estimate its velocity on release.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VelocityTracker {
+    samples: VecDeque<Sample>,
+}
+
+impl VelocityTracker {
+    /// Record a delta, dropping samples older than `window_usec`.
+    pub fn push(&mut self, window_usec: u64, time_usec: u64, dx: f64, dy: f64) {
+        self.samples.push_back(Sample { time_usec, dx, dy });
+        while self
+            .samples
+            .front()
+            .map(|s| time_usec.saturating_sub(s.time_usec) > window_usec)
+            .unwrap_or(false)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Forget all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The velocity in units per millisecond of a motion released at
+    /// `time_usec`.
+    ///
+    /// Returns `None` if the motion rested for more than half of
+    /// `window_usec` before the release or there are too few samples.
+    pub fn release_velocity(&self, window_usec: u64, time_usec: u64) -> Option<(f64, f64)> {
+        let first = self.samples.front()?;
+        let last = self.samples.back()?;
+        if time_usec.saturating_sub(last.time_usec) > window_usec / 2 {
+            return None;
+        }
+        // the first sample only marks the start of the measured interval
+        let elapsed_ms = time_usec.saturating_sub(first.time_usec) as f64 / 1000.0;
+        if elapsed_ms <= 0.0 || self.samples.len() < 2 {
+            return None;
+        }
+        let (sum_x, sum_y) = self
+            .samples
+            .iter()
+            .skip(1)
+            .fold((0.0, 0.0), |(x, y), s| (x + s.dx, y + s.dy));
+        Some((sum_x / elapsed_ms, sum_y / elapsed_ms))
+    }
+}
+
+/// Axes of a finger scroll sequence, see `KineticScroll::finger_scroll`.
+#[derive(Debug, Clone, Copy, Default)]
+struct Sequence {
+    /// Axes that reported a non-zero value
+    active: [bool; 2],
+    /// Active axes that reported the terminating 0 value
+    stopped: [bool; 2],
+}
+
+impl Sequence {
+    /// Record the values of an event and return whether every active axis
+    /// has stopped.
+    fn update(&mut self, values: [Option<f64>; 2]) -> bool {
+        for (axis, value) in values.iter().enumerate() {
+            match value {
+                Some(value) if *value != 0.0 => {
+                    self.active[axis] = true;
+                    self.stopped[axis] = false;
+                }
+                Some(_) => self.stopped[axis] = true,
+                None => {}
+            }
+        }
+        (0..2).all(|axis| !self.active[axis] || self.stopped[axis])
+    }
+}
+
+/// A motion continued after its release with decaying velocity.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Coast {
+    start_usec: u64,
+    last_usec: u64,
+    vx: f64,
+    vy: f64,
+}
+
+impl Coast {
+    /// Start coasting at `time_usec` with a velocity in units per
+    /// millisecond.
+    pub fn new(time_usec: u64, vx: f64, vy: f64) -> Coast {
+        Coast {
+            start_usec: time_usec,
+            last_usec: time_usec,
+            vx,
+            vy,
+        }
+    }
+
+    /// Advance the coast to `time_usec`.
+    ///
+    /// Returns the distance covered since the last step and whether the
+    /// velocity dropped below `min_velocity`, or `None` if no time passed.
+    pub fn advance(
+        &mut self,
+        curve: &DecelerationCurve,
+        time_usec: u64,
+        min_velocity: f64,
+    ) -> Option<(f64, f64, bool)> {
+        if time_usec <= self.last_usec {
+            return None;
+        }
+        let last = (self.last_usec - self.start_usec) as f64 / 1000.0;
+        let now = (time_usec - self.start_usec) as f64 / 1000.0;
+        let dx = curve.distance(self.vx, now) - curve.distance(self.vx, last);
+        let dy = curve.distance(self.vy, now) - curve.distance(self.vy, last);
+        self.last_usec = time_usec;
+        let vx = curve.velocity(self.vx, now);
+        let vy = curve.velocity(self.vy, now);
+        Some((dx, dy, vx.hypot(vy) < min_velocity))
+    }
+}
+
+/// Kinetic (inertial) scrolling for finger-source scroll sequences.
+///
+/// libinput terminates every finger scroll sequence with a scroll value of
+/// 0 and leaves kinetic scrolling to the caller. Feed all events into
+/// `handle_event`: the engine estimates the velocity at the time the
+/// fingers were lifted from the recent finger scroll events and continues
+/// scrolling with decaying deltas following the configured
+/// `DecelerationCurve`.
+///
+/// The engine owns a timer file descriptor that becomes readable whenever
+/// the next kinetic step is due. Add it to your event loop and call
+/// `dispatch` once it is readable. Alternatively drive the engine with your
+/// own timer by calling `frame`.
+///
+/// A new finger scroll sequence or a hold gesture (a finger resting on the
+/// touchpad) on the same device cancels the coast.
+#[derive(Debug)]
+pub struct KineticScroll {
+    timer: OwnedFd,
+    curve: DecelerationCurve,
+    interval: Duration,
+    sample_window_usec: u64,
+    min_velocity: f64,
+    samples: HashMap<Device, VelocityTracker>,
+    sequences: HashMap<Device, Sequence>,
+    coasts: HashMap<Device, Coast>,
+}
+
+impl KineticScroll {
+    /// Create a new kinetic scrolling engine.
+    ///
+    /// By default the velocity decays exponentially with a time constant of
+    /// 325ms, steps are produced every 16ms and coasting stops once the
+    /// velocity drops below 0.01 units per millisecond.
+    pub fn new() -> IoResult<Self> {
+        let fd = unsafe {
+            libc::timerfd_create(
+                libc::CLOCK_MONOTONIC,
+                libc::TFD_NONBLOCK | libc::TFD_CLOEXEC,
+            )
+        };
+        if fd < 0 {
+            return Err(IoError::last_os_error());
+        }
+        Ok(KineticScroll {
+            timer: unsafe { OwnedFd::from_raw_fd(fd) },
+            curve: DecelerationCurve::Exponential {
+                time_constant_ms: 325.0,
+            },
+            interval: Duration::from_millis(16),
+            sample_window_usec: 100_000,
+            min_velocity: 0.01,
+            samples: HashMap::new(),
+            sequences: HashMap::new(),
+            coasts: HashMap::new(),
+        })
+    }
+
+    /// Set the deceleration curve used for new coasts.
+    pub fn set_curve(&mut self, curve: DecelerationCurve) {
+        self.curve = curve;
+    }
+
+    /// Set the interval between two kinetic steps.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Set the window of finger scroll events before the release that is
+    /// used to estimate the release velocity.
+    pub fn set_sample_window(&mut self, window: Duration) {
+        self.sample_window_usec = window.as_micros() as u64;
+    }
+
+    /// Set the velocity in scroll units per millisecond below which no
+    /// coast is started and an ongoing coast is stopped.
+    pub fn set_min_velocity(&mut self, velocity: f64) {
+        self.min_velocity = velocity;
+    }
+
+    /// Check if any device is currently coasting.
+    pub fn is_coasting(&self) -> bool {
+        !self.coasts.is_empty()
+    }
+
+    /// Cancel all ongoing coasts and discard the velocity samples of
+    /// ongoing scroll sequences.
+    pub fn cancel(&mut self) -> IoResult<()> {
+        self.coasts.clear();
+        self.samples.clear();
+        self.sequences.clear();
+        self.arm(false)
+    }
+
+    /// Process an event.
+    ///
+    /// Only finger scroll events and hold gestures are of interest, all
+    /// other events are ignored.
+    pub fn handle_event(&mut self, event: &Event) -> IoResult<()> {
+        match event {
+            #[cfg(feature = "libinput_1_19")]
+            Event::Pointer(PointerEvent::ScrollFinger(event)) => {
+                let value = |axis| {
+                    if event.has_axis(axis) {
+                        Some(event.scroll_value(axis))
+                    } else {
+                        None
+                    }
+                };
+                self.finger_scroll(
+                    event.device(),
+                    event.time_usec(),
+                    value(Axis::Horizontal),
+                    value(Axis::Vertical),
+                )
+            }
+            #[cfg(not(feature = "libinput_1_19"))]
+            Event::Pointer(PointerEvent::Axis(event))
+                if event.axis_source() == AxisSource::Finger =>
+            {
+                let value = |axis| {
+                    if event.has_axis(axis) {
+                        Some(event.axis_value(axis))
+                    } else {
+                        None
+                    }
+                };
+                self.finger_scroll(
+                    event.device(),
+                    event.time_usec(),
+                    value(Axis::Horizontal),
+                    value(Axis::Vertical),
+                )
+            }
+            #[cfg(feature = "libinput_1_19")]
+            Event::Gesture(GestureEvent::Hold(GestureHoldEvent::Begin(event))) => {
+                let device = event.device();
+                self.samples.remove(&device);
+                self.sequences.remove(&device);
+                if self.coasts.remove(&device).is_some() && self.coasts.is_empty() {
+                    self.arm(false)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Read the timer and produce the kinetic steps that are due.
+    ///
+    /// Call this once the file descriptor of this engine is readable.
+    pub fn dispatch(&mut self) -> IoResult<Vec<KineticScrollFrame>> {
+        let mut expirations = 0u64;
+        let res = unsafe {
+            libc::read(
+                self.timer.as_raw_fd(),
+                &mut expirations as *mut u64 as *mut libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        if res < 0 {
+            let err = IoError::last_os_error();
+            if err.kind() != std::io::ErrorKind::WouldBlock {
+                return Err(err);
+            }
+        }
+        let frames = self.frame(monotonic_usec());
+        if self.coasts.is_empty() {
+            self.arm(false)?;
+        }
+        Ok(frames)
+    }
+
+    /// Produce the kinetic steps up to the given time in microseconds
+    /// (`CLOCK_MONOTONIC`, like `time_usec` of events).
+    ///
+    /// This does not touch the timer and can be used to drive the engine
+    /// with an external timer.
+    pub fn frame(&mut self, time_usec: u64) -> Vec<KineticScrollFrame> {
+        let curve = self.curve;
+        let min_velocity = self.min_velocity;
+        let mut frames = Vec::new();
+        self.coasts.retain(|device, coast| {
+            let (dx, dy, finished) = match coast.advance(&curve, time_usec, min_velocity) {
+                Some(step) => step,
+                None => return true,
+            };
+            frames.push(KineticScrollFrame {
+                device: device.clone(),
+                time_usec,
+                dx,
+                dy,
+                finished,
+            });
+            !finished
+        });
+        frames
+    }
+
+    fn finger_scroll(
+        &mut self,
+        device: Device,
+        time_usec: u64,
+        dx: Option<f64>,
+        dy: Option<f64>,
+    ) -> IoResult<()> {
+        // libinput terminates every axis of the sequence separately
+        let stop = self
+            .sequences
+            .entry(device.clone())
+            .or_default()
+            .update([dx, dy]);
+        if !stop {
+            // a new sequence cancels an ongoing coast on this device
+            if self.coasts.remove(&device).is_some() && self.coasts.is_empty() {
+                self.arm(false)?;
+            }
+            if dx.unwrap_or(0.0) == 0.0 && dy.unwrap_or(0.0) == 0.0 {
+                // an axis stopped while the other one is still scrolling
+                return Ok(());
+            }
+            let window = self.sample_window_usec;
+            self.samples.entry(device).or_default().push(
+                window,
+                time_usec,
+                dx.unwrap_or(0.0),
+                dy.unwrap_or(0.0),
+            );
+            return Ok(());
+        }
+
+        self.sequences.remove(&device);
+        // no velocity if the fingers rested before they were lifted
+        let window = self.sample_window_usec;
+        let (vx, vy) = match self
+            .samples
+            .remove(&device)
+            .and_then(|samples| samples.release_velocity(window, time_usec))
+        {
+            Some(velocity) => velocity,
+            None => return Ok(()),
+        };
+        if vx.hypot(vy) < self.min_velocity {
+            return Ok(());
+        }
+        let was_idle = self.coasts.is_empty();
+        self.coasts.insert(device, Coast::new(time_usec, vx, vy));
+        if was_idle {
+            self.arm(true)?;
+        }
+        Ok(())
+    }
+
+    fn arm(&self, enable: bool) -> IoResult<()> {
+        let interval = if enable {
+            libc::timespec {
+                tv_sec: self.interval.as_secs() as libc::time_t,
+                tv_nsec: self.interval.subsec_nanos() as libc::c_long,
+            }
+        } else {
+            libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            }
+        };
+        let spec = libc::itimerspec {
+            it_interval: interval,
+            it_value: interval,
+        };
+        match unsafe {
+            libc::timerfd_settime(self.timer.as_raw_fd(), 0, &spec, std::ptr::null_mut())
+        } {
+            0 => Ok(()),
+            _ => Err(IoError::last_os_error()),
+        }
+    }
+}
+
+fn monotonic_usec() -> u64 {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1_000
+}
+
+impl AsRawFd for KineticScroll {
+    fn as_raw_fd(&self) -> RawFd {
+        self.timer.as_raw_fd()
+    }
+}
+
+impl AsFd for KineticScroll {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.timer.as_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coast, DecelerationCurve, Sequence, VelocityTracker};
+
+    const CURVE: DecelerationCurve = DecelerationCurve::Exponential {
+        time_constant_ms: 100.0,
+    };
+
+    #[test]
+    fn release_velocity_of_moving_motion() {
+        let mut tracker = VelocityTracker::default();
+        tracker.push(100_000, 0, 0.0, 0.0);
+        tracker.push(100_000, 10_000, 5.0, 0.0);
+        tracker.push(100_000, 20_000, 5.0, -2.0);
+        let (vx, vy) = tracker.release_velocity(100_000, 20_000).unwrap();
+        assert!((vx - 0.5).abs() < 1e-9);
+        assert!((vy + 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_release_velocity_after_rest() {
+        let mut tracker = VelocityTracker::default();
+        tracker.push(100_000, 0, 0.0, 0.0);
+        tracker.push(100_000, 10_000, 5.0, 0.0);
+        assert!(tracker.release_velocity(100_000, 70_000).is_none());
+        tracker.clear();
+        assert!(tracker.release_velocity(100_000, 10_000).is_none());
+    }
+
+    #[test]
+    fn coast_decays_and_finishes() {
+        let mut coast = Coast::new(1_000, 1.0, 0.0);
+        assert!(coast.advance(&CURVE, 1_000, 0.01).is_none());
+        let (first, _, finished) = coast.advance(&CURVE, 11_000, 0.01).unwrap();
+        assert!(!finished);
+        let (second, _, _) = coast.advance(&CURVE, 21_000, 0.01).unwrap();
+        assert!(second < first);
+        let mut total = first + second;
+        let mut time = 21_000;
+        loop {
+            time += 10_000;
+            let (dx, _, finished) = coast.advance(&CURVE, time, 0.01).unwrap();
+            total += dx;
+            if finished {
+                break;
+            }
+        }
+        // v0 * time constant minus the remaining tail below min velocity
+        assert!(total > 98.0 && total < 100.0);
+    }
+
+    #[test]
+    fn sequence_stops_once_every_active_axis_stopped() {
+        let mut sequence = Sequence::default();
+        assert!(!sequence.update([Some(1.0), Some(2.0)]));
+        assert!(!sequence.update([None, Some(0.0)]));
+        assert!(!sequence.update([Some(1.0), None]));
+        assert!(sequence.update([Some(0.0), None]));
+    }
+
+    #[test]
+    fn sequence_resumes_stopped_axis() {
+        let mut sequence = Sequence::default();
+        assert!(!sequence.update([None, Some(3.0)]));
+        assert!(sequence.update([None, Some(0.0)]));
+        let mut sequence = Sequence::default();
+        assert!(!sequence.update([None, Some(3.0)]));
+        assert!(!sequence.update([Some(1.0), Some(0.0)]));
+        assert!(!sequence.update([None, Some(2.0)]));
+        assert!(!sequence.update([Some(0.0), None]));
+        assert!(sequence.update([None, Some(0.0)]));
+    }
+}
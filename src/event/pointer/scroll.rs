@@ -0,0 +1,329 @@
+#[cfg(not(feature = "libinput_1_19"))]
+use super::AxisSource;
+#[cfg(feature = "libinput_1_19")]
+use super::PointerScrollEvent;
+use super::{Axis, PointerEvent, PointerEventTrait};
+use crate::{event::EventTrait, Device};
+use std::collections::HashMap;
+
+/// The source of a scroll sequence as seen by `ScrollAccumulator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScrollSource {
+    /// Rotation of a (possibly high-resolution) wheel
+    Wheel,
+    /// Movement of one or more fingers on a device
+    Finger,
+    /// Motion of some device, e.g. button scrolling
+    Continuous,
+}
+
+/// Scroll information of a single axis produced by `ScrollAccumulator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollAxisFrame {
+    /// The device that generated the scroll event
+    pub device: Device,
+    /// The source of the scroll event
+    pub source: ScrollSource,
+    /// The axis this frame belongs to
+    pub axis: Axis,
+    /// The event time in microseconds
+    pub time_usec: u64,
+    /// Smooth scroll delta in pixel-like units, with the positive direction
+    /// being down or right.
+    pub delta: f64,
+    /// The v120 value of the event, 0 for non-wheel sources.
+    pub v120: f64,
+    /// The v120 value accumulated since the last whole notch, after this
+    /// event was applied.
+    pub v120_accumulated: f64,
+    /// Number of discrete steps (legacy wheel clicks) completed by this
+    /// event, 0 for non-wheel sources.
+    ///
+    /// For high-resolution wheels fractions of a notch are accumulated, so
+    /// a step is only emitted once a multiple of 120 is reached.
+    pub discrete: i32,
+    /// Whether this frame ends the scroll sequence on this axis.
+    ///
+    /// Only finger and continuous sources send terminating events. A caller
+    /// may use this information to decide on whether kinetic scrolling
+    /// should be triggered.
+    pub stop: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct AxisState {
+    v120: f64,
+    scrolling: bool,
+}
+
+impl AxisState {
+    /// Accumulate the v120 value of a wheel event, returning the number of
+    /// whole notches completed.
+    fn wheel(&mut self, v120: f64) -> i32 {
+        // a direction change discards the partial notch
+        if self.v120 * v120 < 0.0 {
+            self.v120 = 0.0;
+        }
+        self.v120 += v120;
+        let discrete = (self.v120 / 120.0).trunc();
+        self.v120 -= discrete * 120.0;
+        discrete as i32
+    }
+
+    /// Record the delta of a finger or continuous scroll event, returning
+    /// whether it ends the scroll sequence.
+    fn smooth(&mut self, delta: f64) -> bool {
+        let stop = delta == 0.0;
+        self.scrolling = !stop;
+        self.v120 = 0.0;
+        stop
+    }
+}
+
+/// Accumulates scroll events of all sources into smooth deltas, v120
+/// notches and discrete steps.
+///
+/// Scroll state is tracked per device and per axis. Feed all pointer
+/// events into `handle_event` and use the returned frames instead of
+/// interpreting the different scroll event types individually.
+///
+/// With the `libinput_1_19` feature libinput emits every scroll event
+/// twice, once as a legacy `PointerEvent::Axis` and once as one of the
+/// `PointerEvent::Scroll*` events. The accumulator then ignores the
+/// legacy events.
+#[derive(Debug)]
+pub struct ScrollAccumulator {
+    wheel_click_delta: f64,
+    states: HashMap<(Device, Axis), AxisState>,
+}
+
+impl Default for ScrollAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScrollAccumulator {
+    /// Create a new accumulator.
+    ///
+    /// By default the smooth delta of a wheel click is 15, the value
+    /// libinput uses for most mice.
+    pub fn new() -> Self {
+        ScrollAccumulator {
+            wheel_click_delta: 15.0,
+            states: HashMap::new(),
+        }
+    }
+
+    /// Set the smooth delta of a single wheel click.
+    ///
+    /// This is only used for legacy wheel events, which do not carry a v120
+    /// value on their own.
+    pub fn set_wheel_click_delta(&mut self, delta: f64) {
+        self.wheel_click_delta = delta;
+    }
+
+    /// Check if a finger or continuous scroll sequence is ongoing on the
+    /// given device and axis, i.e. it was not terminated yet.
+    pub fn is_scrolling(&self, device: &Device, axis: Axis) -> bool {
+        self.states
+            .get(&(device.clone(), axis))
+            .map(|state| state.scrolling)
+            .unwrap_or(false)
+    }
+
+    /// Discard the accumulated state of the given device, e.g. on
+    /// `DeviceRemovedEvent`.
+    pub fn remove_device(&mut self, device: &Device) {
+        self.states.retain(|(dev, _), _| dev != device);
+    }
+
+    /// Discard all accumulated state, e.g. on focus change.
+    pub fn reset(&mut self) {
+        self.states.clear();
+    }
+
+    /// Process a pointer event.
+    ///
+    /// Returns one frame for every axis the event has a value for. Events
+    /// that are not scroll events return an empty list.
+    pub fn handle_event(&mut self, event: &PointerEvent) -> Vec<ScrollAxisFrame> {
+        let mut frames = Vec::new();
+        match event {
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollWheel(event) => {
+                for axis in [Axis::Vertical, Axis::Horizontal] {
+                    if event.has_axis(axis) {
+                        frames.push(self.wheel(
+                            event.device(),
+                            axis,
+                            event.time_usec(),
+                            event.scroll_value(axis),
+                            event.scroll_value_v120(axis),
+                        ));
+                    }
+                }
+            }
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollFinger(event) => {
+                for axis in [Axis::Vertical, Axis::Horizontal] {
+                    if event.has_axis(axis) {
+                        frames.push(self.smooth(
+                            event.device(),
+                            ScrollSource::Finger,
+                            axis,
+                            event.time_usec(),
+                            event.scroll_value(axis),
+                        ));
+                    }
+                }
+            }
+            #[cfg(feature = "libinput_1_19")]
+            PointerEvent::ScrollContinuous(event) => {
+                for axis in [Axis::Vertical, Axis::Horizontal] {
+                    if event.has_axis(axis) {
+                        frames.push(self.smooth(
+                            event.device(),
+                            ScrollSource::Continuous,
+                            axis,
+                            event.time_usec(),
+                            event.scroll_value(axis),
+                        ));
+                    }
+                }
+            }
+            #[cfg(not(feature = "libinput_1_19"))]
+            PointerEvent::Axis(event) => {
+                for axis in [Axis::Vertical, Axis::Horizontal] {
+                    if !event.has_axis(axis) {
+                        continue;
+                    }
+                    let value = event.axis_value(axis);
+                    let frame = match event.axis_source() {
+                        AxisSource::Wheel | AxisSource::WheelTilt => {
+                            let discrete = event.axis_value_discrete(axis).unwrap_or(0.0);
+                            let delta = if value != 0.0 {
+                                value
+                            } else {
+                                discrete * self.wheel_click_delta
+                            };
+                            self.wheel(
+                                event.device(),
+                                axis,
+                                event.time_usec(),
+                                delta,
+                                discrete * 120.0,
+                            )
+                        }
+                        AxisSource::Finger => self.smooth(
+                            event.device(),
+                            ScrollSource::Finger,
+                            axis,
+                            event.time_usec(),
+                            value,
+                        ),
+                        AxisSource::Continuous => self.smooth(
+                            event.device(),
+                            ScrollSource::Continuous,
+                            axis,
+                            event.time_usec(),
+                            value,
+                        ),
+                        AxisSource::Unknown(_) => continue,
+                    };
+                    frames.push(frame);
+                }
+            }
+            _ => {}
+        }
+        frames
+    }
+
+    fn wheel(
+        &mut self,
+        device: Device,
+        axis: Axis,
+        time_usec: u64,
+        delta: f64,
+        v120: f64,
+    ) -> ScrollAxisFrame {
+        let state = self.states.entry((device.clone(), axis)).or_default();
+        let discrete = state.wheel(v120);
+        ScrollAxisFrame {
+            device,
+            source: ScrollSource::Wheel,
+            axis,
+            time_usec,
+            delta,
+            v120,
+            v120_accumulated: state.v120,
+            discrete,
+            stop: false,
+        }
+    }
+
+    fn smooth(
+        &mut self,
+        device: Device,
+        source: ScrollSource,
+        axis: Axis,
+        time_usec: u64,
+        delta: f64,
+    ) -> ScrollAxisFrame {
+        let stop = self
+            .states
+            .entry((device.clone(), axis))
+            .or_default()
+            .smooth(delta);
+        ScrollAxisFrame {
+            device,
+            source,
+            axis,
+            time_usec,
+            delta,
+            v120: 0.0,
+            v120_accumulated: 0.0,
+            discrete: 0,
+            stop,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AxisState;
+
+    #[test]
+    fn wheel_keeps_remainder_of_partial_notches() {
+        let mut state = AxisState::default();
+        assert_eq!(state.wheel(90.0), 0);
+        assert_eq!(state.wheel(90.0), 1);
+        assert_eq!(state.v120, 60.0);
+        assert_eq!(state.wheel(300.0), 3);
+        assert_eq!(state.v120, 0.0);
+        assert_eq!(state.wheel(-240.0), -2);
+    }
+
+    #[test]
+    fn wheel_direction_change_discards_remainder() {
+        let mut state = AxisState::default();
+        assert_eq!(state.wheel(100.0), 0);
+        assert_eq!(state.wheel(-30.0), 0);
+        assert_eq!(state.v120, -30.0);
+        assert_eq!(state.wheel(-90.0), -1);
+    }
+
+    #[test]
+    fn smooth_scrolling_between_wheel_events() {
+        let mut state = AxisState::default();
+        assert_eq!(state.wheel(60.0), 0);
+        assert!(!state.smooth(5.0));
+        assert!(state.scrolling);
+        // the finger sequence discarded the partial notch
+        assert_eq!(state.wheel(60.0), 0);
+        assert_eq!(state.wheel(60.0), 1);
+        assert!(!state.smooth(-2.5));
+        assert!(state.smooth(0.0));
+        assert!(!state.scrolling);
+    }
+}
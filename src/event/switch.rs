@@ -104,6 +104,8 @@ pub enum SwitchState {
     Off,
     /// Switch is on
     On,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 ffi_event_struct!(
@@ -131,7 +133,7 @@ impl SwitchToggleEvent {
         match unsafe { ffi::libinput_event_switch_get_switch_state(self.as_raw_mut()) } {
             ffi::libinput_switch_state_LIBINPUT_SWITCH_STATE_OFF => SwitchState::Off,
             ffi::libinput_switch_state_LIBINPUT_SWITCH_STATE_ON => SwitchState::On,
-            _ => panic!("libinput returned invalid 'libinput_switch_state'"),
+            x => SwitchState::Unknown(x),
         }
     }
 }
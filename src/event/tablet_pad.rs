@@ -3,7 +3,9 @@
 pub use super::{keyboard::KeyState, pointer::ButtonState, EventTrait};
 use crate::{ffi, AsRaw, Context, FromRaw, Libinput};
 
+mod control;
 mod mode_group;
+pub use self::control::*;
 pub use self::mode_group::*;
 
 /// Common functions all TabletPad-Events implement.
@@ -123,6 +125,10 @@ impl FromRaw<ffi::libinput_event_tablet_pad> for TabletPadEvent {
             ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_STRIP => Some(
                 TabletPadEvent::Strip(TabletPadStripEvent::try_from_raw(event, context)?),
             ),
+            #[cfg(feature = "libinput_1_26")]
+            ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_DIAL => Some(TabletPadEvent::Dial(
+                TabletPadDialEvent::try_from_raw(event, context)?,
+            )),
             #[cfg(feature = "libinput_1_15")]
             ffi::libinput_event_type_LIBINPUT_EVENT_TABLET_PAD_KEY => Some(TabletPadEvent::Key(
                 TabletPadKeyEvent::try_from_raw(event, context)?,
@@ -186,7 +192,7 @@ impl TabletPadButtonEvent {
         match unsafe { ffi::libinput_event_tablet_pad_get_button_state(self.as_raw_mut()) } {
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_PRESSED => ButtonState::Pressed,
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_RELEASED => ButtonState::Released,
-            _ => panic!("libinput returned invalid 'libinput_button_state'"),
+            x => ButtonState::Unknown(x),
         }
     }
 }
@@ -338,7 +344,7 @@ impl TabletPadKeyEvent {
         match unsafe { ffi::libinput_event_tablet_pad_get_key_state(self.as_raw() as *mut _) } {
             ffi::libinput_key_state_LIBINPUT_KEY_STATE_PRESSED => KeyState::Pressed,
             ffi::libinput_key_state_LIBINPUT_KEY_STATE_RELEASED => KeyState::Released,
-            _ => panic!("libinput returned invalid 'libinput_key_state'"),
+            x => KeyState::Unknown(x),
         }
     }
 }
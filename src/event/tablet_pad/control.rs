@@ -0,0 +1,356 @@
+use super::{RingAxisSource, StripAxisSource, TabletPadEvent, TabletPadEventTrait};
+use crate::{
+    event::{
+        pointer::{Coast, DecelerationCurve, VelocityTracker},
+        EventTrait,
+    },
+    Device,
+};
+use std::collections::HashMap;
+
+/// A continuous control on a device with the `DeviceCapability::TabletPad`
+/// capability, identified by its kind and number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TabletPadControl {
+    /// A ring, see `TabletPadRingEvent::number`
+    Ring(u32),
+    /// A strip, see `TabletPadStripEvent::number`
+    Strip(u32),
+    /// A dial, see `TabletPadDialEvent::number`
+    #[cfg(feature = "libinput_1_26")]
+    Dial(u32),
+}
+
+/// The action the deltas of a `TabletPadControl` are mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum TabletPadControlAction {
+    /// Scroll the content below the cursor
+    Scroll,
+    /// Zoom in or out
+    Zoom,
+    /// Grow or shrink the brush size
+    BrushSize,
+}
+
+/// A relative change of a `TabletPadControl` as produced by
+/// `TabletPadControlConverter`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabletPadControlDelta {
+    /// The device the control belongs to
+    pub device: Device,
+    /// The control that changed
+    pub control: TabletPadControl,
+    /// The action the control is mapped to
+    pub action: TabletPadControlAction,
+    /// The relative change in logical clicks, i.e. a value of 1.0 equals
+    /// a dial delta of 120 or one click of a ring or strip.
+    pub delta: f64,
+    /// The number of whole action steps this delta completed.
+    ///
+    /// Fractional steps are accumulated across events of the same control.
+    pub steps: i32,
+    /// Whether this delta was synthesized by kinetic continuation after
+    /// the finger was lifted.
+    pub kinetic: bool,
+}
+
+#[derive(Debug, Default)]
+struct ControlState {
+    /// Last absolute position, `None` if no finger is on the control
+    position: Option<f64>,
+    /// Recent movements of the current touch
+    velocity: VelocityTracker,
+    /// Fractional steps not yet emitted
+    remainder: f64,
+    /// Kinetic continuation after the finger was lifted
+    coast: Option<Coast>,
+}
+
+/// The movement of a ring from `last` to `position` in logical clicks,
+/// taking the shorter way around.
+fn ring_clicks(last: f64, position: f64, degrees_per_click: f64) -> f64 {
+    let mut degrees = position - last;
+    if degrees > 180.0 {
+        degrees -= 360.0;
+    } else if degrees < -180.0 {
+        degrees += 360.0;
+    }
+    degrees / degrees_per_click
+}
+
+/// Add `delta` to the fractional steps in `remainder` and take the whole
+/// steps out.
+fn take_steps(remainder: &mut f64, delta: f64, clicks_per_step: f64) -> i32 {
+    *remainder += delta / clicks_per_step;
+    let steps = remainder.trunc();
+    *remainder -= steps;
+    steps as i32
+}
+
+/// Converts ring, strip and dial events of tablet pads into uniform
+/// relative deltas.
+///
+/// Rings report absolute angles and strips report absolute positions,
+/// both send a position of -1 once the finger is lifted. Dials report
+/// relative v120 values. This converter turns all of them into deltas
+/// measured in logical clicks, handles the wraparound of rings, ignores
+/// the jump caused by putting down a finger at a new position and
+/// accumulates the deltas into whole steps of the mapped
+/// `TabletPadControlAction`.
+///
+/// If a ring or strip interaction with the `Finger` source ends while the
+/// control still moves, the converter continues it with decaying deltas
+/// like `KineticScroll`. Those are retrieved by calling `kinetic_frame`
+/// periodically while `is_coasting` returns `true`.
+#[derive(Debug)]
+pub struct TabletPadControlConverter {
+    ring_degrees_per_click: f64,
+    strip_clicks_per_length: f64,
+    curve: DecelerationCurve,
+    min_velocity: f64,
+    sample_window_usec: u64,
+    actions: HashMap<TabletPadControl, TabletPadControlAction>,
+    clicks_per_step: HashMap<TabletPadControlAction, f64>,
+    states: HashMap<(Device, TabletPadControl), ControlState>,
+}
+
+impl Default for TabletPadControlConverter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TabletPadControlConverter {
+    /// Create a new converter.
+    ///
+    /// By default a ring click equals 15 degrees (like most mouse wheels), a
+    /// strip is 10 clicks long, every control is mapped to
+    /// `TabletPadControlAction::Scroll` and every action step equals one click.
+    /// Kinetic continuations decay exponentially with a time constant of
+    /// 325ms and stop below 0.005 clicks per millisecond.
+    pub fn new() -> Self {
+        TabletPadControlConverter {
+            ring_degrees_per_click: 15.0,
+            strip_clicks_per_length: 10.0,
+            curve: DecelerationCurve::Exponential {
+                time_constant_ms: 325.0,
+            },
+            min_velocity: 0.005,
+            sample_window_usec: 100_000,
+            actions: HashMap::new(),
+            clicks_per_step: HashMap::new(),
+            states: HashMap::new(),
+        }
+    }
+
+    /// Set how many degrees of ring rotation equal one logical click.
+    pub fn set_ring_degrees_per_click(&mut self, degrees: f64) {
+        self.ring_degrees_per_click = degrees;
+    }
+
+    /// Set how many logical clicks the full length of a strip equals.
+    pub fn set_strip_clicks_per_length(&mut self, clicks: f64) {
+        self.strip_clicks_per_length = clicks;
+    }
+
+    /// Set the kinetic continuation parameters.
+    ///
+    /// ## Arguments
+    ///
+    /// - curve - Deceleration curve of new kinetic continuations
+    /// - min_velocity - Velocity in logical clicks per millisecond below which no
+    ///   kinetic continuation is started or an ongoing one is stopped
+    pub fn set_kinetic(&mut self, curve: DecelerationCurve, min_velocity: f64) {
+        self.curve = curve;
+        self.min_velocity = min_velocity;
+    }
+
+    /// Map the given control to an action.
+    ///
+    /// The mapping applies to the control on all devices.
+    pub fn set_action(&mut self, control: TabletPadControl, action: TabletPadControlAction) {
+        self.actions.insert(control, action);
+    }
+
+    /// Return the action the given control is mapped to.
+    pub fn action(&self, control: TabletPadControl) -> TabletPadControlAction {
+        self.actions
+            .get(&control)
+            .copied()
+            .unwrap_or(TabletPadControlAction::Scroll)
+    }
+
+    /// Set how many logical clicks are needed for a single step of the given
+    /// action.
+    pub fn set_clicks_per_step(&mut self, action: TabletPadControlAction, clicks: f64) {
+        self.clicks_per_step.insert(action, clicks);
+    }
+
+    /// Check if any control is currently continued kinetically.
+    pub fn is_coasting(&self) -> bool {
+        self.states.values().any(|state| state.coast.is_some())
+    }
+
+    /// Stop all kinetic continuations, e.g. on focus change.
+    pub fn stop_coasting(&mut self) {
+        for state in self.states.values_mut() {
+            state.coast = None;
+        }
+    }
+
+    /// Forget all state of the given device, e.g. on `DeviceRemovedEvent`.
+    pub fn remove_device(&mut self, device: &Device) {
+        self.states.retain(|(dev, _), _| dev != device);
+    }
+
+    /// Process a tablet pad event.
+    ///
+    /// Returns the resulting delta for ring, strip and dial events. Button
+    /// and key events, the first event of a new touch and the terminating
+    /// event of a touch return `None`.
+    pub fn handle_event(&mut self, event: &TabletPadEvent) -> Option<TabletPadControlDelta> {
+        match event {
+            TabletPadEvent::Ring(event) => self.handle_absolute(
+                event.device(),
+                TabletPadControl::Ring(event.number()),
+                event.position(),
+                event.time_usec(),
+                event.source() == RingAxisSource::Finger,
+            ),
+            TabletPadEvent::Strip(event) => self.handle_absolute(
+                event.device(),
+                TabletPadControl::Strip(event.number()),
+                event.position(),
+                event.time_usec(),
+                event.source() == StripAxisSource::Finger,
+            ),
+            #[cfg(feature = "libinput_1_26")]
+            TabletPadEvent::Dial(event) => {
+                let device = event.device();
+                let control = TabletPadControl::Dial(event.number());
+                let delta = event.dial_v120() / 120.0;
+                Some(self.delta(device, control, delta, false))
+            }
+            _ => None,
+        }
+    }
+
+    /// Produce the kinetic deltas for all coasting controls up to the given
+    /// time in microseconds (`CLOCK_MONOTONIC`, like `time_usec` of events).
+    pub fn kinetic_frame(&mut self, time_usec: u64) -> Vec<TabletPadControlDelta> {
+        let curve = self.curve;
+        let min_velocity = self.min_velocity;
+        let mut deltas = Vec::new();
+        for ((device, control), state) in self.states.iter_mut() {
+            let coast = match &mut state.coast {
+                Some(coast) => coast,
+                None => continue,
+            };
+            let (delta, _, finished) = match coast.advance(&curve, time_usec, min_velocity) {
+                Some(step) => step,
+                None => continue,
+            };
+            if finished {
+                state.coast = None;
+            }
+            deltas.push((device.clone(), *control, delta));
+        }
+        deltas
+            .into_iter()
+            .map(|(device, control, delta)| self.delta(device, control, delta, true))
+            .collect()
+    }
+
+    fn handle_absolute(
+        &mut self,
+        device: Device,
+        control: TabletPadControl,
+        position: f64,
+        time_usec: u64,
+        finger: bool,
+    ) -> Option<TabletPadControlDelta> {
+        let ring_degrees_per_click = self.ring_degrees_per_click;
+        let strip_clicks_per_length = self.strip_clicks_per_length;
+        let min_velocity = self.min_velocity;
+        let window = self.sample_window_usec;
+        let state = self.states.entry((device.clone(), control)).or_default();
+
+        if position < 0.0 {
+            // finger lifted, start coasting if the control was still moving
+            // right before, a finger resting before the lift has no velocity
+            state.position = None;
+            state.coast = state
+                .velocity
+                .release_velocity(window, time_usec)
+                .filter(|(velocity, _)| finger && velocity.abs() >= min_velocity)
+                .map(|(velocity, _)| Coast::new(time_usec, velocity, 0.0));
+            state.velocity.clear();
+            return None;
+        }
+
+        let last = match state.position.replace(position) {
+            Some(last) => last,
+            None => {
+                // new touch, the absolute jump is not a movement
+                state.velocity.clear();
+                state.velocity.push(window, time_usec, 0.0, 0.0);
+                state.coast = None;
+                return None;
+            }
+        };
+
+        let delta = if let TabletPadControl::Ring(_) = control {
+            ring_clicks(last, position, ring_degrees_per_click)
+        } else {
+            (position - last) * strip_clicks_per_length
+        };
+        state.velocity.push(window, time_usec, delta, 0.0);
+
+        Some(self.delta(device, control, delta, false))
+    }
+
+    fn delta(
+        &mut self,
+        device: Device,
+        control: TabletPadControl,
+        delta: f64,
+        kinetic: bool,
+    ) -> TabletPadControlDelta {
+        let action = self.action(control);
+        let clicks_per_step = self.clicks_per_step.get(&action).copied().unwrap_or(1.0);
+        let state = self.states.entry((device.clone(), control)).or_default();
+        let steps = take_steps(&mut state.remainder, delta, clicks_per_step);
+        TabletPadControlDelta {
+            device,
+            control,
+            action,
+            delta,
+            steps,
+            kinetic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ring_clicks, take_steps};
+
+    #[test]
+    fn ring_takes_shorter_way_around() {
+        assert_eq!(ring_clicks(10.0, 40.0, 15.0), 2.0);
+        assert_eq!(ring_clicks(350.0, 20.0, 15.0), 2.0);
+        assert_eq!(ring_clicks(20.0, 350.0, 15.0), -2.0);
+    }
+
+    #[test]
+    fn steps_accumulate_fractions() {
+        let mut remainder = 0.0;
+        assert_eq!(take_steps(&mut remainder, 0.6, 1.0), 0);
+        assert_eq!(take_steps(&mut remainder, 0.6, 1.0), 1);
+        assert!((remainder - 0.2).abs() < 1e-9);
+        assert_eq!(take_steps(&mut remainder, -1.4, 1.0), -1);
+        assert!((remainder + 0.2).abs() < 1e-9);
+        assert_eq!(take_steps(&mut remainder, 4.2, 2.0), 1);
+    }
+}
@@ -424,6 +424,8 @@ pub enum ProximityState {
     Out,
     /// In proximity
     In,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 ffi_event_struct! {
@@ -464,7 +466,7 @@ impl TabletToolProximityEvent {
             ffi::libinput_tablet_tool_proximity_state_LIBINPUT_TABLET_TOOL_PROXIMITY_STATE_IN => {
                 ProximityState::In
             }
-            _ => panic!("libinput returned invalid 'libinput_tablet_tool_proximity_state'"),
+            x => ProximityState::Unknown(x),
         }
     }
 }
@@ -479,6 +481,8 @@ pub enum TipState {
     Up,
     /// Touching the surface
     Down,
+    /// A state unknown to this library
+    Unknown(u32),
 }
 
 ffi_event_struct! {
@@ -510,7 +514,7 @@ impl TabletToolTipEvent {
         match unsafe { ffi::libinput_event_tablet_tool_get_tip_state(self.as_raw_mut()) } {
             ffi::libinput_tablet_tool_tip_state_LIBINPUT_TABLET_TOOL_TIP_UP => TipState::Up,
             ffi::libinput_tablet_tool_tip_state_LIBINPUT_TABLET_TOOL_TIP_DOWN => TipState::Down,
-            _ => panic!("libinput returned invalid 'libinput_tablet_tool_top_state'"),
+            x => TipState::Unknown(x),
         }
     }
 }
@@ -542,7 +546,7 @@ impl TabletToolButtonEvent {
         match unsafe { ffi::libinput_event_tablet_tool_get_button_state(self.as_raw_mut()) } {
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_PRESSED => ButtonState::Pressed,
             ffi::libinput_button_state_LIBINPUT_BUTTON_STATE_RELEASED => ButtonState::Released,
-            _ => panic!("libinput returned invalid 'libinput_button_state'"),
+            x => ButtonState::Unknown(x),
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{ffi, AsRaw, FromRaw};
-#[cfg(feature = "libinput_1_26")]
+#[cfg(any(feature = "libinput_1_26", feature = "dlopen"))]
 use crate::{DeviceConfigError, DeviceConfigResult};
 
 /// Available tool types for a device with the `DeviceCapability::TabletTool` capability.
@@ -168,18 +168,9 @@ impl TabletTool {
     /// i.e. that (maximium - minimum > N) for an implementation-defined value of N.
     #[cfg(feature = "libinput_1_26")]
     pub fn config_pressure_range_set(&self, minimum: f64, maximum: f64) -> DeviceConfigResult {
-        match unsafe {
+        DeviceConfigError::from_ffi(unsafe {
             ffi::libinput_tablet_tool_config_pressure_range_set(self.as_raw_mut(), minimum, maximum)
-        } {
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_SUCCESS => Ok(()),
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_UNSUPPORTED => {
-                Err(DeviceConfigError::Unsupported)
-            }
-            ffi::libinput_config_status_LIBINPUT_CONFIG_STATUS_INVALID => {
-                Err(DeviceConfigError::Invalid)
-            }
-            _ => panic!("libinput returned invalid 'libinput_config_status'"),
-        }
+        })
     }
 
     #[cfg(feature = "libinput_1_26")]
@@ -219,7 +210,7 @@ impl TabletTool {
 }
 
 /// Used to change tablet tool eraser mode using [`TabletTool::config_eraser_button_set_mode`]
-#[cfg(feature = "libinput_1_29")]
+#[cfg(any(feature = "libinput_1_29", feature = "dlopen"))]
 #[doc(alias = "libinput_config_eraser_button_mode")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -233,9 +224,11 @@ pub enum EraserButtonMode {
     /// Use the default hardware behavior of the tool.
     /// libinput does not modify the behavior of the eraser button (if any).
     Default,
+    /// A mode unknown to this library
+    Unknown(u32),
 }
 
-#[cfg(feature = "libinput_1_29")]
+#[cfg(any(feature = "libinput_1_29", feature = "dlopen"))]
 impl EraserButtonMode {
     fn from_ffi(v: ffi::libinput_config_eraser_button_mode) -> Self {
         match v {
@@ -245,7 +238,7 @@ impl EraserButtonMode {
             ffi::libinput_config_eraser_button_mode_LIBINPUT_CONFIG_ERASER_BUTTON_DEFAULT => {
                 Self::Default
             }
-            _ => unreachable!(),
+            x => Self::Unknown(x),
         }
     }
 
@@ -257,53 +250,80 @@ impl EraserButtonMode {
             EraserButtonMode::Default => {
                 ffi::libinput_config_eraser_button_mode_LIBINPUT_CONFIG_ERASER_BUTTON_DEFAULT
             }
+            EraserButtonMode::Unknown(x) => *x as ffi::libinput_config_eraser_button_mode,
         }
     }
 }
 
-#[cfg(feature = "libinput_1_29")]
+/// With the `dlopen` feature, these functions are resolved at runtime. If the
+/// libinput library in use does not provide them, they return
+/// [`DeviceConfigError::UnsupportedVersion`].
+#[cfg(any(feature = "libinput_1_29", feature = "dlopen"))]
 impl TabletTool {
     /// Get the button configured to emulate an eraser for this tool.
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_get_button")]
-    pub fn config_eraser_button_get_button(&self) -> Option<u32> {
+    pub fn config_eraser_button_get_button(&self) -> Result<Option<u32>, DeviceConfigError> {
         let btn = unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_get_button(self.as_raw_mut()) as u32
-        };
-        (btn != 0).then_some(btn)
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_get_button(
+                self.as_raw_mut()
+            ))
+        }
+        .ok_or(DeviceConfigError::UnsupportedVersion)? as u32;
+        Ok((btn != 0).then_some(btn))
     }
 
     /// Get the default button configured to emulate an eraser for this tool.
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_get_default_button")]
-    pub fn config_eraser_button_get_default_button(&self) -> Option<u32> {
+    pub fn config_eraser_button_get_default_button(
+        &self,
+    ) -> Result<Option<u32>, DeviceConfigError> {
         let btn = unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_get_default_button(self.as_raw_mut())
-                as u32
-        };
-        (btn != 0).then_some(btn)
+            ffi_dyn!(
+                libinput_tablet_tool_config_eraser_button_get_default_button(self.as_raw_mut())
+            )
+        }
+        .ok_or(DeviceConfigError::UnsupportedVersion)? as u32;
+        Ok((btn != 0).then_some(btn))
     }
 
     /// Get the mode for the eraser button.
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_get_mode")]
-    pub fn config_eraser_button_get_mode(&self) -> EraserButtonMode {
-        EraserButtonMode::from_ffi(unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_get_mode(self.as_raw_mut())
-        })
+    pub fn config_eraser_button_get_mode(&self) -> Result<EraserButtonMode, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_get_mode(
+                self.as_raw_mut()
+            ))
+        }
+        .map(EraserButtonMode::from_ffi)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
     /// Get the default mode for the eraser button.
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_get_default_mode")]
-    pub fn config_eraser_button_get_default_mode(&self) -> EraserButtonMode {
-        EraserButtonMode::from_ffi(unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_get_default_mode(self.as_raw_mut())
-        })
+    pub fn config_eraser_button_get_default_mode(
+        &self,
+    ) -> Result<EraserButtonMode, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_get_default_mode(
+                self.as_raw_mut()
+            ))
+        }
+        .map(EraserButtonMode::from_ffi)
+        .ok_or(DeviceConfigError::UnsupportedVersion)
     }
 
-    ffi_func!(
     /// Check if a tool can change the behavior of or to a firmware eraser button.
     ///
     /// returns: Non-zero if the device can be set to change to an eraser on button
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_get_modes")]
-    pub fn config_eraser_button_get_modes, ffi::libinput_tablet_tool_config_eraser_button_get_modes, u32);
+    pub fn config_eraser_button_get_modes(&self) -> Result<u32, DeviceConfigError> {
+        unsafe {
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_get_modes(
+                self.as_raw_mut()
+            ))
+        }
+        .ok_or(DeviceConfigError::UnsupportedVersion)
+    }
 
     /// Set a button to be the eraser button for this tool.
     /// This configuration has no effect unless the caller also sets
@@ -311,19 +331,30 @@ impl TabletTool {
     /// [`TabletTool::config_eraser_button_set_mode()`].
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_set_button")]
     pub fn config_eraser_button_set_button(&self, button: u32) -> DeviceConfigResult {
-        DeviceConfigError::from_ffi(unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_set_button(self.as_raw_mut(), button)
-        })
+        unsafe {
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_set_button(
+                self.as_raw_mut(),
+                button
+            ))
+        }
+        .map_or(
+            Err(DeviceConfigError::UnsupportedVersion),
+            DeviceConfigError::from_ffi,
+        )
     }
 
     /// Change the eraser button behavior on a tool.
     #[doc(alias = "libinput_tablet_tool_config_eraser_button_set_mode")]
     pub fn config_eraser_button_set_mode(&self, mode: EraserButtonMode) -> DeviceConfigResult {
-        DeviceConfigError::from_ffi(unsafe {
-            ffi::libinput_tablet_tool_config_eraser_button_set_mode(
+        unsafe {
+            ffi_dyn!(libinput_tablet_tool_config_eraser_button_set_mode(
                 self.as_raw_mut(),
                 mode.as_ffi(),
-            )
-        })
+            ))
+        }
+        .map_or(
+            Err(DeviceConfigError::UnsupportedVersion),
+            DeviceConfigError::from_ffi,
+        )
     }
 }
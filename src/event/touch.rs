@@ -46,8 +46,7 @@ pub trait TouchEventSlot: AsRaw<ffi::libinput_event_touch> {
     fn slot(&self) -> Option<u32> {
         match unsafe { ffi::libinput_event_touch_get_slot(self.as_raw_mut()) } {
             x if x >= 0 => Some(x as u32),
-            -1 => None,
-            _ => panic!("libinput_event_touch_get_slot returned undocumentated value!"),
+            _ => None,
         }
     }
 }
@@ -0,0 +1,317 @@
+use crate::{Device, Libinput};
+use std::{
+    collections::HashMap,
+    ffi::{CString, OsStr},
+    fmt, fs,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    mem,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    },
+    path::{Path, PathBuf},
+};
+
+/// `_IOC(_IOC_READ, 'E', 0x20, 4)`, i.e. `EVIOCGBIT(0, 4)`
+const EVIOCGBIT_TYPES: libc::c_ulong = 0x8004_4520;
+
+bitflags::bitflags! {
+    /// Event types supported by an evdev device node, see
+    /// [`evdev_event_types`].
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    pub struct EvdevEventTypes: u32 {
+        /// Synchronization events
+        const SYN = 1 << 0x00;
+        /// Keys and buttons
+        const KEY = 1 << 0x01;
+        /// Relative axes, e.g. mice
+        const REL = 1 << 0x02;
+        /// Absolute axes, e.g. touchpads and tablets
+        const ABS = 1 << 0x03;
+        /// Miscellaneous events
+        const MSC = 1 << 0x04;
+        /// Switches
+        const SW = 1 << 0x05;
+        /// LEDs
+        const LED = 1 << 0x11;
+        /// Sounds
+        const SND = 1 << 0x12;
+        /// Autorepeat
+        const REP = 1 << 0x14;
+        /// Force feedback
+        const FF = 1 << 0x15;
+        /// Power button events
+        const PWR = 1 << 0x16;
+        /// Force feedback status
+        const FF_STATUS = 1 << 0x17;
+    }
+}
+
+/// Query the event types supported by the evdev device node at `path`.
+///
+/// The node is opened through the `LibinputInterface` of `context`.
+pub fn evdev_event_types(context: &Libinput, path: &Path) -> IoResult<EvdevEventTypes> {
+    let fd = context
+        .open_device_node(path, libc::O_RDONLY | libc::O_NONBLOCK | libc::O_CLOEXEC)
+        .map_err(|errno| IoError::from_raw_os_error(errno.abs()))?;
+    let mut bits: u32 = 0;
+    let result = if unsafe { libc::ioctl(fd.as_raw_fd(), EVIOCGBIT_TYPES as _, &mut bits) } < 0 {
+        Err(IoError::last_os_error())
+    } else {
+        Ok(EvdevEventTypes::from_bits_truncate(bits))
+    };
+    context.close_device_node(fd);
+    result
+}
+
+/// Create a non-blocking inotify instance watching `directory`.
+pub(crate) fn inotify_watch(directory: &Path, mask: u32) -> IoResult<OwnedFd> {
+    let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+    if fd < 0 {
+        return Err(IoError::last_os_error());
+    }
+    let inotify = unsafe { OwnedFd::from_raw_fd(fd) };
+    let path = CString::new(directory.as_os_str().as_bytes())
+        .map_err(|err| IoError::new(ErrorKind::InvalidInput, err))?;
+    if unsafe { libc::inotify_add_watch(inotify.as_raw_fd(), path.as_ptr(), mask) } < 0 {
+        return Err(IoError::last_os_error());
+    }
+    Ok(inotify)
+}
+
+/// Read all pending events of a non-blocking inotify instance, passing
+/// their mask and file name to `handler`.
+pub(crate) fn read_inotify<F>(inotify: BorrowedFd<'_>, mut handler: F) -> IoResult<()>
+where
+    F: FnMut(u32, &OsStr),
+{
+    // u64 to satisfy the alignment of `inotify_event`
+    let mut buf = [0u64; 512];
+    loop {
+        let len = unsafe {
+            libc::read(
+                inotify.as_raw_fd(),
+                buf.as_mut_ptr() as *mut libc::c_void,
+                mem::size_of_val(&buf),
+            )
+        };
+        if len < 0 {
+            let err = IoError::last_os_error();
+            return match err.kind() {
+                ErrorKind::WouldBlock => Ok(()),
+                ErrorKind::Interrupted => continue,
+                _ => Err(err),
+            };
+        }
+
+        let bytes = buf.as_ptr() as *const u8;
+        let mut offset = 0;
+        while offset + mem::size_of::<libc::inotify_event>() <= len as usize {
+            let event =
+                unsafe { (bytes.add(offset) as *const libc::inotify_event).read_unaligned() };
+            let name_ptr = unsafe { bytes.add(offset + mem::size_of::<libc::inotify_event>()) };
+            let name = unsafe { std::slice::from_raw_parts(name_ptr, event.len as usize) };
+            let name = OsStr::from_bytes(name.split(|&b| b == 0).next().unwrap_or(&[]));
+            offset += mem::size_of::<libc::inotify_event>() + event.len as usize;
+            handler(event.mask, name);
+        }
+    }
+}
+
+fn is_event_node(name: &OsStr) -> bool {
+    name.to_str()
+        .and_then(|name| name.strip_prefix("event"))
+        .map_or(false, |num| {
+            !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+type Filter = Box<dyn FnMut(&Path, EvdevEventTypes) -> bool>;
+
+/// Hotplug support for contexts created with
+/// [`Libinput::new_from_path`], for environments without udev.
+///
+/// Watches a directory, `/dev/input` by default, for evdev device nodes
+/// with inotify and adds and removes them from the context. Nodes are only
+/// added if their supported event types pass a filter, which by default
+/// accepts keys, relative and absolute axes and switches.
+///
+/// The hotplug helper exposes a file descriptor, which becomes readable
+/// once [`PathHotplug::dispatch`] needs to be called.
+///
+/// libinput replaces all devices when the context is resumed, so the
+/// devices to remove are looked up in the
+/// [`DeviceRegistry`](crate::DeviceRegistry) of the context. After a
+/// resume, process the events of the context before dispatching the helper.
+///
+/// ```no_run
+/// use input::{interface::DirectInterface, Libinput, PathHotplug};
+///
+/// let mut input = Libinput::new_from_path(DirectInterface::new()).unwrap();
+/// let mut hotplug = PathHotplug::new().unwrap();
+/// hotplug.scan(&mut input);
+/// loop {
+///     // Wait for `hotplug` or `input` to become readable
+///     hotplug.dispatch(&mut input).unwrap();
+///     input.dispatch().unwrap();
+///     for event in &mut input {
+///         println!("Got event: {:?}", event);
+///     }
+/// }
+/// ```
+pub struct PathHotplug {
+    inotify: OwnedFd,
+    directory: PathBuf,
+    filter: Filter,
+    devices: HashMap<PathBuf, Device>,
+}
+
+impl fmt::Debug for PathHotplug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathHotplug")
+            .field("inotify", &self.inotify)
+            .field("directory", &self.directory)
+            .field("devices", &self.devices)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PathHotplug {
+    /// Watch `/dev/input` for device nodes.
+    pub fn new() -> IoResult<PathHotplug> {
+        PathHotplug::with_directory("/dev/input")
+    }
+
+    /// Watch the given directory for device nodes.
+    pub fn with_directory<P: AsRef<Path>>(directory: P) -> IoResult<PathHotplug> {
+        let directory = directory.as_ref().to_path_buf();
+        let inotify = inotify_watch(
+            &directory,
+            libc::IN_CREATE
+                | libc::IN_ATTRIB
+                | libc::IN_MOVED_TO
+                | libc::IN_DELETE
+                | libc::IN_MOVED_FROM,
+        )?;
+        Ok(PathHotplug {
+            inotify,
+            directory,
+            filter: Box::new(|_, types| {
+                types.intersects(
+                    EvdevEventTypes::KEY
+                        | EvdevEventTypes::REL
+                        | EvdevEventTypes::ABS
+                        | EvdevEventTypes::SW,
+                )
+            }),
+            devices: HashMap::new(),
+        })
+    }
+
+    /// Replace the filter deciding which device nodes are added.
+    ///
+    /// The closure receives the path of the node and its supported event
+    /// types. Nodes whose event types cannot be queried are never added.
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: FnMut(&Path, EvdevEventTypes) -> bool + 'static,
+    {
+        self.filter = Box::new(filter);
+        self
+    }
+
+    /// Devices currently added by this helper, by path.
+    pub fn devices(&self, context: &Libinput) -> Vec<(PathBuf, Device)> {
+        self.devices
+            .iter()
+            .map(|(path, device)| (path.clone(), current_device(context, path, device)))
+            .collect()
+    }
+
+    /// Add all device nodes already present in the watched directory.
+    ///
+    /// Returns the newly added devices.
+    pub fn scan(&mut self, context: &mut Libinput) -> Vec<Device> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                log::warn!("Failed to scan {}: {}", self.directory.display(), _err);
+                return Vec::new();
+            }
+        };
+        let mut names = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+            .iter()
+            .filter_map(|name| self.add(context, name))
+            .collect()
+    }
+
+    /// Process pending inotify events without blocking, adding and removing
+    /// devices from `context`.
+    pub fn dispatch(&mut self, context: &mut Libinput) -> IoResult<()> {
+        let mut events = Vec::new();
+        let result = read_inotify(self.inotify.as_fd(), |mask, name| {
+            events.push((mask, name.to_os_string()))
+        });
+        for (mask, name) in events {
+            if mask & (libc::IN_DELETE | libc::IN_MOVED_FROM) != 0 {
+                self.remove(context, &name);
+            } else if mask & (libc::IN_CREATE | libc::IN_ATTRIB | libc::IN_MOVED_TO) != 0 {
+                // Nodes may only become accessible once their permissions are set
+                self.add(context, &name);
+            }
+        }
+        result
+    }
+
+    fn add(&mut self, context: &mut Libinput, name: &OsStr) -> Option<Device> {
+        if !is_event_node(name) {
+            return None;
+        }
+        let path = self.directory.join(name);
+        if self.devices.contains_key(&path) {
+            return None;
+        }
+        let types = evdev_event_types(context, &path).ok()?;
+        if !(self.filter)(&path, types) {
+            return None;
+        }
+        let device = context.path_add_device(path.to_str()?)?;
+        self.devices.insert(path, device.clone());
+        Some(device)
+    }
+
+    fn remove(&mut self, context: &mut Libinput, name: &OsStr) {
+        let path = self.directory.join(name);
+        if let Some(device) = self.devices.remove(&path) {
+            let device = current_device(context, &path, &device);
+            context.path_remove_device(device);
+        }
+    }
+}
+
+/// The device currently added for `path`, which differs from the device
+/// returned by `path_add_device` once the context was resumed.
+fn current_device(context: &Libinput, path: &Path, added: &Device) -> Device {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .and_then(|sysname| context.device_registry().by_sysname(sysname))
+        .unwrap_or_else(|| added.clone())
+}
+
+impl AsRawFd for PathHotplug {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
+    }
+}
+
+impl AsFd for PathHotplug {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inotify.as_fd()
+    }
+}
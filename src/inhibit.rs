@@ -0,0 +1,360 @@
+use crate::{config_store, Device, DeviceConfigError, DeviceKey, Libinput, SendEventsMode};
+use std::collections::HashMap;
+
+/// How an [`Inhibitor`] disables a device, see [`Device::inhibit`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InhibitMode {
+    /// Do not send events through the device, see
+    /// `SendEventsMode::DISABLED`.
+    Disabled,
+    /// Do not send events through the device while an external pointer
+    /// device is plugged in, see `SendEventsMode::DISABLED_ON_EXTERNAL_MOUSE`.
+    DisabledOnExternalMouse,
+}
+
+impl From<InhibitMode> for SendEventsMode {
+    fn from(mode: InhibitMode) -> Self {
+        match mode {
+            InhibitMode::Disabled => SendEventsMode::DISABLED,
+            InhibitMode::DisabledOnExternalMouse => SendEventsMode::DISABLED_ON_EXTERNAL_MOUSE,
+        }
+    }
+}
+
+/// An active inhibition of a device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Inhibitor {
+    /// Name of the subsystem holding the inhibition, e.g. `"lid"`
+    pub name: String,
+    /// How the device is inhibited
+    pub mode: InhibitMode,
+}
+
+/// Inhibitors active on a single device.
+#[derive(Debug)]
+struct DeviceInhibitors {
+    /// Send-events mode before the first inhibitor
+    base: SendEventsMode,
+    /// Active inhibitors by id, oldest first, with a clone of the device
+    /// their guard was taken on
+    inhibitors: Vec<(u64, Inhibitor, Device)>,
+}
+
+/// How much of a device a send-events mode disables.
+fn restrictiveness(mode: SendEventsMode) -> u8 {
+    if mode.contains(SendEventsMode::DISABLED) {
+        2
+    } else if mode.contains(SendEventsMode::DISABLED_ON_EXTERNAL_MOUSE) {
+        1
+    } else {
+        0
+    }
+}
+
+impl DeviceInhibitors {
+    fn mode(&self) -> SendEventsMode {
+        let active = |mode| self.inhibitors.iter().any(|(_, inh, _)| inh.mode == mode);
+        let mode = if active(InhibitMode::Disabled) {
+            SendEventsMode::DISABLED
+        } else if active(InhibitMode::DisabledOnExternalMouse) {
+            SendEventsMode::DISABLED_ON_EXTERNAL_MOUSE
+        } else {
+            return self.base;
+        };
+        // Inhibitors never enable a device that was disabled before
+        if restrictiveness(mode) > restrictiveness(self.base) {
+            mode
+        } else {
+            self.base
+        }
+    }
+}
+
+/// Inhibitors active on the devices of a context.
+#[derive(Debug, Default)]
+pub(crate) struct InhibitState {
+    next_id: u64,
+    // Keyed like the `ConfigStore`, so inhibitors survive replugging
+    devices: HashMap<DeviceKey, DeviceInhibitors>,
+}
+
+fn set_mode(device: &Device, mode: SendEventsMode) -> Result<(), DeviceConfigError> {
+    // Inhibitions are temporary and must not end up in the `ConfigStore`
+    config_store::without_remembering(&device.context(), || {
+        device.config_send_events_set_mode(mode)
+    })
+}
+
+/// The devices currently present with `key`, or `device` if the context
+/// did not see any device with the key, e.g. as its events were not
+/// processed yet.
+fn devices_with_key(context: &Libinput, key: &DeviceKey, device: &Device) -> Vec<Device> {
+    let registry = context.device_registry();
+    match registry.seen(key) {
+        Some(_) => registry.by_key(key),
+        None => vec![device.clone()],
+    }
+}
+
+/// Apply the send-events mode of the inhibitors active for the key of a
+/// newly added device.
+pub(crate) fn device_added(context: &Libinput, device: &Device) {
+    let state = match context.inhibit_state() {
+        Some(state) => state,
+        None => return,
+    };
+    let mode = {
+        let state = state.borrow();
+        if state.devices.is_empty() {
+            return;
+        }
+        match state
+            .devices
+            .get(&config_store::device_key(context, device))
+        {
+            Some(inhibitors) => inhibitors.mode(),
+            None => return,
+        }
+    };
+    if let Err(_err) = set_mode(device, mode) {
+        #[cfg(feature = "log")]
+        log::warn!(
+            "Failed to inhibit {}: {}",
+            device.sysname().unwrap_or_default(),
+            _err
+        );
+    }
+}
+
+/// Guard of an inhibition taken with [`Device::inhibit`].
+///
+/// Dropping the guard releases the inhibition and recomputes the
+/// send-events mode of the device from the remaining inhibitors.
+#[must_use = "Dropping the guard immediately releases the inhibition"]
+#[derive(Debug)]
+pub struct DeviceInhibitor {
+    device: Device,
+    // `None` for contexts without shared state
+    key: Option<DeviceKey>,
+    id: u64,
+    inhibitor: Inhibitor,
+    // Mode to restore for contexts without shared state
+    base: Option<SendEventsMode>,
+}
+
+impl DeviceInhibitor {
+    /// The inhibited device.
+    ///
+    /// The device may have been removed since, the inhibition then applies
+    /// to devices with the same [`DeviceKey`] instead.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The name of the inhibitor.
+    pub fn name(&self) -> &str {
+        &self.inhibitor.name
+    }
+
+    /// How the device is inhibited.
+    pub fn mode(&self) -> InhibitMode {
+        self.inhibitor.mode
+    }
+
+    /// Release the inhibition, equivalent to dropping the guard.
+    pub fn release(self) {}
+}
+
+impl Drop for DeviceInhibitor {
+    fn drop(&mut self) {
+        let context = self.device.context();
+        let (mode, devices) = match (context.inhibit_state(), &self.key) {
+            (Some(state), Some(key)) => {
+                let mut state = state.borrow_mut();
+                let inhibitors = match state.devices.get_mut(key) {
+                    Some(inhibitors) => inhibitors,
+                    None => return,
+                };
+                let previous = inhibitors.mode();
+                inhibitors.inhibitors.retain(|(id, _, _)| *id != self.id);
+                let mode = inhibitors.mode();
+                if inhibitors.inhibitors.is_empty() {
+                    state.devices.remove(key);
+                }
+                if mode == previous {
+                    return;
+                }
+                drop(state);
+                (mode, devices_with_key(&context, key, &self.device))
+            }
+            _ => match self.base {
+                Some(base) => (base, vec![self.device.clone()]),
+                None => return,
+            },
+        };
+        for device in &devices {
+            if let Err(_err) = set_mode(device, mode) {
+                #[cfg(feature = "log")]
+                log::warn!(
+                    "Failed to restore send-events mode of {}: {}",
+                    device.sysname().unwrap_or_default(),
+                    _err
+                );
+            }
+        }
+    }
+}
+
+impl Device {
+    /// Inhibit this device on behalf of a named subsystem.
+    ///
+    /// Independent subsystems, e.g. lid switch handling, a screen locker or
+    /// a user toggle, may each inhibit a device without interfering with
+    /// each other. The send-events mode of the device is derived from all
+    /// active inhibitors: `InhibitMode::Disabled` takes precedence over
+    /// `InhibitMode::DisabledOnExternalMouse`. Once the last inhibitor is
+    /// dropped, the mode the device had before the first inhibitor is
+    /// restored. Inhibitors never make a device less disabled than it was
+    /// before the first inhibitor.
+    ///
+    /// Inhibitors are tracked by the [`DeviceKey`] of the device and applied
+    /// again when a device with the same key is added, e.g. after being
+    /// replugged or on [`Libinput::resume`].
+    ///
+    /// Fails with `DeviceConfigError::Unsupported` if the device does not
+    /// support the requested mode. The send-events mode should not be set
+    /// directly while the device is inhibited.
+    ///
+    /// ```no_run
+    /// # use input::{Device, InhibitMode};
+    /// # fn lid_closed(touchpad: &Device) {
+    /// let lid = touchpad.inhibit("lid", InhibitMode::Disabled).unwrap();
+    /// let locker = touchpad.inhibit("screen-lock", InhibitMode::Disabled).unwrap();
+    /// drop(lid);
+    /// // Still disabled until the screen is unlocked
+    /// assert_eq!(touchpad.inhibitors().len(), 1);
+    /// drop(locker);
+    /// # }
+    /// ```
+    pub fn inhibit<N: Into<String>>(
+        &self,
+        name: N,
+        mode: InhibitMode,
+    ) -> Result<DeviceInhibitor, DeviceConfigError> {
+        if !self
+            .config_send_events_modes()
+            .contains(SendEventsMode::from(mode))
+        {
+            return Err(DeviceConfigError::Unsupported);
+        }
+        let mut guard = DeviceInhibitor {
+            device: self.clone(),
+            key: None,
+            id: 0,
+            inhibitor: Inhibitor {
+                name: name.into(),
+                mode,
+            },
+            base: None,
+        };
+        let context = self.context();
+        let state = match context.inhibit_state() {
+            Some(state) => state,
+            None => {
+                let base = self.config_send_events_mode();
+                set_mode(self, mode.into())?;
+                guard.base = Some(base);
+                return Ok(guard);
+            }
+        };
+
+        let key = config_store::device_key(&context, self);
+        let (previous, mode) = {
+            let mut state = state.borrow_mut();
+            guard.id = state.next_id;
+            state.next_id += 1;
+            let inhibitors = state
+                .devices
+                .entry(key.clone())
+                .or_insert_with(|| DeviceInhibitors {
+                    base: self.config_send_events_mode(),
+                    inhibitors: Vec::new(),
+                });
+            let previous = inhibitors.mode();
+            inhibitors
+                .inhibitors
+                .push((guard.id, guard.inhibitor.clone(), self.clone()));
+            (previous, inhibitors.mode())
+        };
+        if mode != previous {
+            if let Err(err) = set_mode(self, mode) {
+                // Unregister the inhibitor, so dropping the guard does nothing
+                let mut state = state.borrow_mut();
+                if let Some(inhibitors) = state.devices.get_mut(&key) {
+                    inhibitors.inhibitors.retain(|(id, _, _)| *id != guard.id);
+                    if inhibitors.inhibitors.is_empty() {
+                        state.devices.remove(&key);
+                    }
+                }
+                drop(state);
+                return Err(err);
+            }
+        }
+        guard.key = Some(key);
+        Ok(guard)
+    }
+
+    /// The inhibitors currently active on this device, oldest first.
+    pub fn inhibitors(&self) -> Vec<Inhibitor> {
+        let context = self.context();
+        let state = match context.inhibit_state() {
+            Some(state) => state.borrow(),
+            None => return Vec::new(),
+        };
+        if state.devices.is_empty() {
+            return Vec::new();
+        }
+        state
+            .devices
+            .get(&config_store::device_key(&context, self))
+            .map(|inh| {
+                inh.inhibitors
+                    .iter()
+                    .map(|(_, inhibitor, _)| inhibitor.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Libinput {
+    /// All inhibited devices of this context together with their active
+    /// inhibitors, see [`Device::inhibit`].
+    ///
+    /// Devices that were removed while inhibited are listed again once a
+    /// device with the same [`DeviceKey`] is added.
+    pub fn inhibited_devices(&self) -> Vec<(Device, Vec<Inhibitor>)> {
+        let state = match self.inhibit_state() {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+        let state = state.borrow();
+        let mut devices = Vec::new();
+        for (key, inh) in &state.devices {
+            let inhibitors = inh
+                .inhibitors
+                .iter()
+                .map(|(_, inhibitor, _)| inhibitor.clone())
+                .collect::<Vec<_>>();
+            let device = match inh.inhibitors.first() {
+                Some((_, _, device)) => device,
+                None => continue,
+            };
+            for device in devices_with_key(self, key, device) {
+                devices.push((device, inhibitors.clone()));
+            }
+        }
+        devices
+    }
+}
@@ -0,0 +1,11 @@
+//! Ready-made [`LibinputInterface`](crate::LibinputInterface) implementations
+
+mod broker;
+mod direct;
+#[cfg(feature = "libseat")]
+mod libseat;
+
+pub use self::broker::*;
+pub use self::direct::*;
+#[cfg(feature = "libseat")]
+pub use self::libseat::*;
@@ -0,0 +1,417 @@
+use crate::LibinputInterface;
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fmt,
+    io::{Error as IoError, ErrorKind, Read, Result as IoResult, Write},
+    mem,
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+        net::UnixStream,
+    },
+    path::Path,
+    ptr,
+};
+
+const OPEN: u8 = 0;
+const CLOSE: u8 = 1;
+
+/// Upper bound for the size of a single message, well above `PATH_MAX`.
+const MAX_MESSAGE_LEN: usize = 8192;
+
+/// Open flags clients may request, everything else is rejected with `EINVAL`.
+const ALLOWED_FLAGS: i32 = libc::O_ACCMODE | libc::O_NONBLOCK | libc::O_CLOEXEC;
+
+fn protocol_error(msg: &str) -> IoError {
+    IoError::new(ErrorKind::InvalidData, msg)
+}
+
+/// Send a length-prefixed message, optionally passing a file descriptor
+/// along with it using `SCM_RIGHTS`.
+fn send_message(stream: &UnixStream, payload: &[u8], fd: Option<BorrowedFd<'_>>) -> IoResult<()> {
+    let mut data = Vec::with_capacity(4 + payload.len());
+    data.extend_from_slice(&(payload.len() as u32).to_ne_bytes());
+    data.extend_from_slice(payload);
+
+    let mut iov = libc::iovec {
+        iov_base: data.as_mut_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    // u64 to satisfy the alignment of `cmsghdr`
+    let mut cmsg_buf = [0u64; 8];
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    if let Some(fd) = fd {
+        unsafe {
+            msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) as _;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<RawFd>() as u32) as _;
+            ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut RawFd, fd.as_raw_fd());
+        }
+    }
+
+    let sent = loop {
+        match unsafe { libc::sendmsg(stream.as_raw_fd(), &msg, libc::MSG_NOSIGNAL) } {
+            x if x < 0 => {
+                let err = IoError::last_os_error();
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            x => break x as usize,
+        }
+    };
+    // The file descriptor has been passed with the first byte already
+    let mut writer = stream;
+    writer.write_all(&data[sent..])
+}
+
+/// Receive a length-prefixed message and the file descriptor passed with it,
+/// if any. Returns `None` once the peer closed the connection.
+fn recv_message(stream: &UnixStream) -> IoResult<Option<(Vec<u8>, Option<OwnedFd>)>> {
+    let mut header = [0u8; 4];
+    let mut iov = libc::iovec {
+        iov_base: header.as_mut_ptr() as *mut libc::c_void,
+        iov_len: header.len(),
+    };
+    let mut cmsg_buf = [0u64; 8];
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = mem::size_of_val(&cmsg_buf) as _;
+
+    let received = loop {
+        match unsafe { libc::recvmsg(stream.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) } {
+            x if x < 0 => {
+                let err = IoError::last_os_error();
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            x => break x as usize,
+        }
+    };
+
+    let mut fd = None;
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg) as *const RawFd;
+                let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                for i in 0..len / mem::size_of::<RawFd>() {
+                    // Take ownership of every fd, so unexpected ones get closed
+                    let received = OwnedFd::from_raw_fd(ptr::read_unaligned(data.add(i)));
+                    fd.get_or_insert(received);
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    if received == 0 {
+        return Ok(None);
+    }
+    let mut reader = stream;
+    reader.read_exact(&mut header[received..])?;
+    let len = u32::from_ne_bytes(header) as usize;
+    if len > MAX_MESSAGE_LEN {
+        return Err(protocol_error("Message too large"));
+    }
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some((payload, fd)))
+}
+
+/// Privileged half of a privilege-separated [`LibinputInterface`].
+///
+/// The broker serves requests of [`BrokerInterface`]s connected over a Unix
+/// socket by opening and closing devices through a wrapped interface, which
+/// decides whether a path may be opened. Clients may only request the
+/// access mode, `O_NONBLOCK` and `O_CLOEXEC` as open flags. Combined with
+/// [`DirectInterface`](super::DirectInterface) this validates requested paths
+/// against its policy and allows revoking all devices handed out to clients.
+///
+/// ```no_run
+/// use input::interface::{Broker, BrokerInterface, DirectInterface};
+/// use input::Libinput;
+/// use std::os::unix::net::UnixStream;
+///
+/// let (broker_socket, client_socket) = UnixStream::pair().unwrap();
+/// // Typically in a forked, privileged process
+/// std::thread::spawn(move || {
+///     let mut broker = Broker::new(DirectInterface::new());
+///     broker.serve(&broker_socket).unwrap();
+/// });
+///
+/// // In the sandboxed process
+/// let mut input = Libinput::new_with_udev(BrokerInterface::new(client_socket)).unwrap();
+/// input.udev_assign_seat("seat0").unwrap();
+/// ```
+pub struct Broker<I: LibinputInterface> {
+    interface: I,
+    next_id: u64,
+}
+
+impl<I: LibinputInterface + fmt::Debug> fmt::Debug for Broker<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broker")
+            .field("interface", &self.interface)
+            .finish()
+    }
+}
+
+impl<I: LibinputInterface> Broker<I> {
+    /// Create a broker opening devices through the given interface.
+    pub fn new(interface: I) -> Broker<I> {
+        Broker {
+            interface,
+            next_id: 0,
+        }
+    }
+
+    /// Access the wrapped interface.
+    pub fn interface(&mut self) -> &mut I {
+        &mut self.interface
+    }
+
+    /// Serve requests of the client connected to `stream` until it
+    /// disconnects.
+    ///
+    /// Devices still open once the client disconnects are closed.
+    pub fn serve(&mut self, stream: &UnixStream) -> IoResult<()> {
+        let mut devices = HashMap::new();
+        let result = loop {
+            match self.dispatch(stream, &mut devices) {
+                Ok(true) => {}
+                Ok(false) => break Ok(()),
+                Err(err) => break Err(err),
+            }
+        };
+        for (_, fd) in devices.drain() {
+            self.interface.close_restricted(fd);
+        }
+        result
+    }
+
+    /// Handle a single request, returning `false` once the client
+    /// disconnected.
+    fn dispatch(
+        &mut self,
+        stream: &UnixStream,
+        devices: &mut HashMap<u64, OwnedFd>,
+    ) -> IoResult<bool> {
+        let payload = match recv_message(stream)? {
+            Some((payload, _)) => payload,
+            None => return Ok(false),
+        };
+        match payload.split_first() {
+            Some((&OPEN, args)) if args.len() >= 4 => {
+                let flags = i32::from_ne_bytes([args[0], args[1], args[2], args[3]]);
+                let path = Path::new(OsStr::from_bytes(&args[4..]));
+                let result = if flags & !ALLOWED_FLAGS != 0 {
+                    Err(libc::EINVAL)
+                } else {
+                    self.interface.open_restricted(path, flags)
+                };
+                match result {
+                    Ok(fd) => {
+                        let id = self.next_id;
+                        self.next_id += 1;
+                        let mut reply = 0i32.to_ne_bytes().to_vec();
+                        reply.extend_from_slice(&id.to_ne_bytes());
+                        let sent = send_message(stream, &reply, Some(fd.as_fd()));
+                        devices.insert(id, fd);
+                        sent?;
+                    }
+                    Err(errno) => {
+                        let errno = match errno.abs() {
+                            0 => libc::EIO,
+                            errno => errno,
+                        };
+                        let mut reply = errno.to_ne_bytes().to_vec();
+                        reply.extend_from_slice(&0u64.to_ne_bytes());
+                        send_message(stream, &reply, None)?;
+                    }
+                }
+            }
+            Some((&CLOSE, args)) if args.len() == 8 => {
+                let mut id = [0u8; 8];
+                id.copy_from_slice(args);
+                if let Some(fd) = devices.remove(&u64::from_ne_bytes(id)) {
+                    self.interface.close_restricted(fd);
+                }
+            }
+            _ => return Err(protocol_error("Invalid request")),
+        }
+        Ok(true)
+    }
+}
+
+/// Sandboxed half of a privilege-separated [`LibinputInterface`].
+///
+/// Requests devices from a [`Broker`] connected over a Unix socket and
+/// receives their file descriptors with `SCM_RIGHTS`. Closing a device asks
+/// the broker to close its copy as well.
+///
+/// Communication failures are reported to libinput as `EIO`.
+#[derive(Debug)]
+pub struct BrokerInterface {
+    stream: UnixStream,
+    devices: HashMap<RawFd, u64>,
+}
+
+impl BrokerInterface {
+    /// Create an interface requesting devices from the broker connected to
+    /// `stream`.
+    pub fn new(stream: UnixStream) -> BrokerInterface {
+        BrokerInterface {
+            stream,
+            devices: HashMap::new(),
+        }
+    }
+
+    fn request_open(&mut self, path: &Path, flags: i32) -> IoResult<Result<OwnedFd, i32>> {
+        let mut request = vec![OPEN];
+        request.extend_from_slice(&flags.to_ne_bytes());
+        request.extend_from_slice(path.as_os_str().as_bytes());
+        send_message(&self.stream, &request, None)?;
+
+        let (reply, fd) =
+            recv_message(&self.stream)?.ok_or_else(|| IoError::from(ErrorKind::UnexpectedEof))?;
+        if reply.len() != 12 {
+            return Err(protocol_error("Invalid reply"));
+        }
+        let errno = i32::from_ne_bytes([reply[0], reply[1], reply[2], reply[3]]);
+        let mut id = [0u8; 8];
+        id.copy_from_slice(&reply[4..]);
+        match (errno, fd) {
+            (0, Some(fd)) => {
+                self.devices.insert(fd.as_raw_fd(), u64::from_ne_bytes(id));
+                Ok(Ok(fd))
+            }
+            (0, None) => Err(protocol_error("Missing file descriptor")),
+            (errno, _) => Ok(Err(errno)),
+        }
+    }
+}
+
+impl LibinputInterface for BrokerInterface {
+    fn open_restricted(&mut self, path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+        match self.request_open(path, flags) {
+            Ok(result) => result,
+            Err(_err) => {
+                #[cfg(feature = "log")]
+                log::warn!("Failed to request {} from broker: {}", path.display(), _err);
+                Err(libc::EIO)
+            }
+        }
+    }
+
+    fn close_restricted(&mut self, fd: OwnedFd) {
+        if let Some(id) = self.devices.remove(&fd.as_raw_fd()) {
+            let mut request = vec![CLOSE];
+            request.extend_from_slice(&id.to_ne_bytes());
+            if let Err(_err) = send_message(&self.stream, &request, None) {
+                #[cfg(feature = "log")]
+                log::warn!("Failed to close device at broker: {}", _err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Broker, BrokerInterface};
+    use crate::LibinputInterface;
+    use std::{
+        fs::OpenOptions,
+        os::unix::{fs::OpenOptionsExt, io::OwnedFd, net::UnixStream},
+        path::Path,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    #[derive(Debug, Default)]
+    struct Log {
+        opened: Vec<i32>,
+        closed: usize,
+    }
+
+    /// Opens `/dev/null`, fails with errno 0 for `/zero` and `ENOENT`
+    /// otherwise.
+    struct TestInterface(Arc<Mutex<Log>>);
+
+    impl LibinputInterface for TestInterface {
+        fn open_restricted(&mut self, path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+            self.0.lock().unwrap().opened.push(flags);
+            match path.to_str() {
+                Some("/dev/null") => OpenOptions::new()
+                    .read(true)
+                    .custom_flags(flags)
+                    .open(path)
+                    .map(Into::into)
+                    .map_err(|err| err.raw_os_error().unwrap()),
+                Some("/zero") => Err(0),
+                _ => Err(libc::ENOENT),
+            }
+        }
+
+        fn close_restricted(&mut self, _fd: OwnedFd) {
+            self.0.lock().unwrap().closed += 1;
+        }
+    }
+
+    #[test]
+    fn open_close_and_disconnect() {
+        let log = Arc::new(Mutex::new(Log::default()));
+        let (broker_socket, client_socket) = UnixStream::pair().unwrap();
+        let interface = TestInterface(log.clone());
+        let broker = thread::spawn(move || Broker::new(interface).serve(&broker_socket));
+
+        let mut client = BrokerInterface::new(client_socket);
+        let flags = libc::O_RDONLY | libc::O_NONBLOCK | libc::O_CLOEXEC;
+        let fd = client
+            .open_restricted(Path::new("/dev/null"), flags)
+            .unwrap();
+        assert_eq!(
+            client
+                .open_restricted(Path::new("/missing"), flags)
+                .unwrap_err(),
+            libc::ENOENT
+        );
+        assert_eq!(
+            client
+                .open_restricted(Path::new("/zero"), flags)
+                .unwrap_err(),
+            libc::EIO
+        );
+        assert_eq!(
+            client
+                .open_restricted(Path::new("/dev/null"), flags | libc::O_CREAT)
+                .unwrap_err(),
+            libc::EINVAL
+        );
+
+        client.close_restricted(fd);
+        // Another request makes sure the close was handled
+        let fd = client
+            .open_restricted(Path::new("/dev/null"), flags)
+            .unwrap();
+        assert_eq!(log.lock().unwrap().closed, 1);
+
+        // Devices still open are closed on disconnect
+        drop(client);
+        broker.join().unwrap().unwrap();
+        drop(fd);
+        let log = log.lock().unwrap();
+        assert_eq!(log.closed, 2);
+        assert_eq!(log.opened, vec![flags; 4]);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::LibinputInterface;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    fs::{self, OpenOptions},
+    io::{Error as IoError, Result as IoResult},
+    os::unix::{
+        fs::OpenOptionsExt,
+        io::{AsRawFd, OwnedFd, RawFd},
+    },
+    path::{Path, PathBuf},
+    ptr,
+    rc::Rc,
+};
+
+/// `_IOW('E', 0x91, int)`
+const EVIOCREVOKE: libc::c_ulong = 0x4004_4591;
+
+type Policy = Box<dyn FnMut(&Path, i32) -> bool>;
+type Opener = Box<dyn FnMut(&Path, i32) -> Result<OwnedFd, i32>>;
+type OpenFds = Rc<RefCell<HashMap<RawFd, OpenFd>>>;
+
+#[derive(Debug)]
+struct OpenFd {
+    path: PathBuf,
+    revoked: bool,
+}
+
+/// Check if the path is an evdev device node, i.e. `/dev/input/event<N>`.
+///
+/// This is the default policy of [`DirectInterface`].
+pub fn is_evdev_node(path: &Path) -> bool {
+    path.parent() == Some(Path::new("/dev/input"))
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("event"))
+            .map_or(false, |num| {
+                !num.is_empty() && num.bytes().all(|b| b.is_ascii_digit())
+            })
+}
+
+/// Open the device at the given path with the flags provided, as done by
+/// [`DirectInterface`] unless configured otherwise.
+///
+/// Returns a positive errno on failure.
+pub fn open_device(path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+    let access = flags & libc::O_ACCMODE;
+    OpenOptions::new()
+        .custom_flags(flags)
+        .read(access == libc::O_RDONLY || access == libc::O_RDWR)
+        .write(access == libc::O_WRONLY || access == libc::O_RDWR)
+        .open(path)
+        .map(OwnedFd::from)
+        .map_err(|err| err.raw_os_error().unwrap_or(libc::EIO))
+}
+
+/// A [`LibinputInterface`] opening devices directly.
+///
+/// Paths are resolved to their canonical form and only opened if accepted by
+/// the policy, which defaults to [`is_evdev_node`]. Rejected paths fail with
+/// `EACCES`. All devices are opened with `O_CLOEXEC`.
+///
+/// The interface keeps track of all file descriptors currently handed out to
+/// libinput, so they can be revoked through a [`Revoker`] when the session
+/// loses access to the devices, e.g. on a VT switch.
+///
+/// ```no_run
+/// use input::{interface::DirectInterface, Libinput};
+///
+/// let interface = DirectInterface::builder()
+///     .policy(|path, _flags| path.starts_with("/dev/input"))
+///     .build();
+/// let revoker = interface.revoker();
+/// let mut input = Libinput::new_with_udev(interface).unwrap();
+/// input.udev_assign_seat("seat0").unwrap();
+///
+/// // Once the session becomes inactive
+/// revoker.revoke_all().unwrap();
+/// input.suspend();
+/// ```
+pub struct DirectInterface {
+    policy: Policy,
+    opener: Opener,
+    fds: OpenFds,
+}
+
+impl DirectInterface {
+    /// Create a new interface opening evdev device nodes only.
+    pub fn new() -> DirectInterface {
+        DirectInterface::builder().build()
+    }
+
+    /// Create a builder to configure a custom policy.
+    pub fn builder() -> DirectInterfaceBuilder {
+        DirectInterfaceBuilder::default()
+    }
+
+    /// Get a handle to revoke the file descriptors opened by this interface.
+    pub fn revoker(&self) -> Revoker {
+        Revoker {
+            fds: self.fds.clone(),
+        }
+    }
+
+    /// Canonical paths of all devices currently opened by this interface.
+    pub fn open_paths(&self) -> Vec<PathBuf> {
+        self.revoker().open_paths()
+    }
+}
+
+impl Default for DirectInterface {
+    fn default() -> Self {
+        DirectInterface::new()
+    }
+}
+
+impl fmt::Debug for DirectInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectInterface")
+            .field("fds", &self.fds)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LibinputInterface for DirectInterface {
+    fn open_restricted(&mut self, path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+        let path = fs::canonicalize(path).map_err(|err| err.raw_os_error().unwrap_or(libc::EIO))?;
+        if !(self.policy)(&path, flags) {
+            #[cfg(feature = "log")]
+            log::warn!("Refusing to open {}", path.display());
+            return Err(libc::EACCES);
+        }
+        let fd = (self.opener)(&path, flags | libc::O_CLOEXEC)?;
+        self.fds.borrow_mut().insert(
+            fd.as_raw_fd(),
+            OpenFd {
+                path,
+                revoked: false,
+            },
+        );
+        Ok(fd)
+    }
+
+    fn close_restricted(&mut self, fd: OwnedFd) {
+        self.fds.borrow_mut().remove(&fd.as_raw_fd());
+    }
+}
+
+/// Builder for a [`DirectInterface`] with a custom policy.
+pub struct DirectInterfaceBuilder {
+    policy: Policy,
+    opener: Opener,
+}
+
+impl Default for DirectInterfaceBuilder {
+    fn default() -> Self {
+        DirectInterfaceBuilder {
+            policy: Box::new(|path, _| is_evdev_node(path)),
+            opener: Box::new(open_device),
+        }
+    }
+}
+
+impl fmt::Debug for DirectInterfaceBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectInterfaceBuilder")
+            .finish_non_exhaustive()
+    }
+}
+
+impl DirectInterfaceBuilder {
+    /// Replace the policy deciding which paths may be opened.
+    ///
+    /// The closure receives the canonical path and the open flags and returns
+    /// whether the device may be opened.
+    pub fn policy<F>(mut self, policy: F) -> Self
+    where
+        F: FnMut(&Path, i32) -> bool + 'static,
+    {
+        self.policy = Box::new(policy);
+        self
+    }
+
+    /// Replace the function used to open devices accepted by the policy,
+    /// which defaults to [`open_device`].
+    ///
+    /// The closure receives the canonical path and the open flags including
+    /// `O_CLOEXEC` and returns the file descriptor or a positive errno.
+    pub fn open_with<F>(mut self, opener: F) -> Self
+    where
+        F: FnMut(&Path, i32) -> Result<OwnedFd, i32> + 'static,
+    {
+        self.opener = Box::new(opener);
+        self
+    }
+
+    /// Create the configured interface.
+    pub fn build(self) -> DirectInterface {
+        DirectInterface {
+            policy: self.policy,
+            opener: self.opener,
+            fds: OpenFds::default(),
+        }
+    }
+}
+
+/// Handle to revoke the file descriptors opened by a [`DirectInterface`].
+#[derive(Debug, Clone)]
+pub struct Revoker {
+    fds: OpenFds,
+}
+
+impl Revoker {
+    /// Revoke access to all devices currently opened by the interface using
+    /// `EVIOCREVOKE`.
+    ///
+    /// Revoked file descriptors stay open and tracked until libinput closes
+    /// them, which happens on [`Libinput::suspend`](crate::Libinput::suspend)
+    /// or when the device is removed. Revoking continues on failure, in which
+    /// case the first error is returned.
+    ///
+    /// Returns the number of newly revoked file descriptors.
+    pub fn revoke_all(&self) -> IoResult<usize> {
+        let mut revoked = 0;
+        let mut result = Ok(());
+        for (fd, entry) in self.fds.borrow_mut().iter_mut() {
+            if entry.revoked {
+                continue;
+            }
+            if unsafe { libc::ioctl(*fd, EVIOCREVOKE as _, ptr::null::<libc::c_void>()) } < 0 {
+                if result.is_ok() {
+                    result = Err(IoError::last_os_error());
+                }
+            } else {
+                entry.revoked = true;
+                revoked += 1;
+            }
+        }
+        result.map(|()| revoked)
+    }
+
+    /// Canonical paths of all devices currently opened by the interface.
+    pub fn open_paths(&self) -> Vec<PathBuf> {
+        self.fds
+            .borrow()
+            .values()
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{Error, Libinput, LibinputInterface};
+use libseat_sys as ffi;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    ffi::{CStr, CString},
+    fmt,
+    io::{Error as IoError, Result as IoResult},
+    os::unix::{
+        ffi::OsStrExt,
+        io::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+    },
+    path::Path,
+    ptr,
+    rc::Rc,
+};
+
+static LISTENER: ffi::libseat_seat_listener = ffi::libseat_seat_listener {
+    enable_seat: Some(enable_seat),
+    disable_seat: Some(disable_seat),
+};
+
+unsafe extern "C" fn enable_seat(_seat: *mut ffi::libseat, userdata: *mut libc::c_void) {
+    if let Some(inner) = (userdata as *const Inner).as_ref() {
+        inner.pending.set(Some(true));
+    }
+}
+
+unsafe extern "C" fn disable_seat(_seat: *mut ffi::libseat, userdata: *mut libc::c_void) {
+    if let Some(inner) = (userdata as *const Inner).as_ref() {
+        inner.pending.set(Some(false));
+    }
+}
+
+struct Inner {
+    seat: Cell<*mut ffi::libseat>,
+    active: Cell<bool>,
+    // State change requested by libseat and not yet handled by `dispatch`
+    pending: Cell<Option<bool>>,
+    // Device ids of the file descriptors handed out to libinput
+    devices: RefCell<HashMap<RawFd, libc::c_int>>,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let seat = self.seat.get();
+        if !seat.is_null() {
+            unsafe { ffi::libseat_close_seat(seat) };
+        }
+    }
+}
+
+/// A seat session opened through libseat, using seatd, systemd-logind or
+/// direct access depending on the available backend.
+///
+/// The session must be dispatched with [`LibseatSession::dispatch`] whenever
+/// its file descriptor, see [`LibseatSession::fd`], becomes readable. Dispatching suspends the given
+/// [`Libinput`] context when the session is disabled and resumes it once the
+/// session is enabled again. Devices are opened through the session by
+/// [`LibseatInterface`].
+///
+/// The backend may be chosen with the `LIBSEAT_BACKEND` environment variable,
+/// e.g. to run against a locally spawned `seatd` by setting it to `seatd` and
+/// `SEATD_SOCK` to the socket of that instance.
+///
+/// ```no_run
+/// use input::{interface::LibseatSession, Libinput};
+///
+/// let session = LibseatSession::open().unwrap();
+/// let mut input = Libinput::new_with_udev(session.interface()).unwrap();
+/// input.udev_assign_seat(&session.seat_name()).unwrap();
+/// loop {
+///     // Wait for `session` or `input` to become readable
+///     session.dispatch(&mut input).unwrap();
+///     input.dispatch().unwrap();
+///     for event in &mut input {
+///         println!("Got event: {:?}", event);
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct LibseatSession {
+    inner: Rc<Inner>,
+}
+
+impl fmt::Debug for LibseatSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LibseatSession")
+            .field("seat", &self.inner.seat.get())
+            .field("active", &self.inner.active.get())
+            .finish()
+    }
+}
+
+impl LibseatSession {
+    /// Open the seat of the current session.
+    pub fn open() -> IoResult<LibseatSession> {
+        let inner = Rc::new(Inner {
+            seat: Cell::new(ptr::null_mut()),
+            active: Cell::new(false),
+            pending: Cell::new(None),
+            devices: RefCell::new(HashMap::new()),
+        });
+        let seat =
+            unsafe { ffi::libseat_open_seat(&LISTENER, Rc::as_ptr(&inner) as *mut libc::c_void) };
+        if seat.is_null() {
+            return Err(IoError::last_os_error());
+        }
+        inner.seat.set(seat);
+        // The seat is usually enabled while opening it
+        if let Some(active) = inner.pending.take() {
+            inner.active.set(active);
+        }
+        Ok(LibseatSession { inner })
+    }
+
+    /// Create a [`LibinputInterface`] opening devices through this session.
+    pub fn interface(&self) -> LibseatInterface {
+        LibseatInterface {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Name of the opened seat.
+    pub fn seat_name(&self) -> String {
+        unsafe { CStr::from_ptr(ffi::libseat_seat_name(self.inner.seat.get())) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Whether the session is currently active and devices may be opened.
+    pub fn is_active(&self) -> bool {
+        self.inner.active.get()
+    }
+
+    /// Request a switch to the given session, e.g. a virtual terminal.
+    pub fn switch_session(&self, session: i32) -> IoResult<()> {
+        match unsafe { ffi::libseat_switch_session(self.inner.seat.get(), session) } {
+            x if x < 0 => Err(IoError::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The file descriptor to poll for session events.
+    pub fn fd(&self) -> IoResult<BorrowedFd<'_>> {
+        match unsafe { ffi::libseat_get_fd(self.inner.seat.get()) } {
+            x if x < 0 => Err(IoError::last_os_error()),
+            fd => Ok(unsafe { BorrowedFd::borrow_raw(fd) }),
+        }
+    }
+
+    /// Process pending session events without blocking.
+    ///
+    /// If the session was disabled, `context` is suspended, closing all of
+    /// its devices, before the change is acknowledged to libseat. If the
+    /// session was enabled, `context` is suspended and resumed, so devices
+    /// that failed to open while the session was inactive are opened as well.
+    pub fn dispatch(&self, context: &mut Libinput) -> Result<(), Error> {
+        if unsafe { ffi::libseat_dispatch(self.inner.seat.get(), 0) } < 0 {
+            return Err(IoError::last_os_error().into());
+        }
+        match self.inner.pending.take() {
+            Some(false) => {
+                self.inner.active.set(false);
+                context.suspend();
+                if unsafe { ffi::libseat_disable_seat(self.inner.seat.get()) } < 0 {
+                    return Err(IoError::last_os_error().into());
+                }
+            }
+            Some(true) => {
+                self.inner.active.set(true);
+                // Resuming a context that was never suspended does nothing,
+                // e.g. if the session was inactive while seat devices were
+                // added by `udev_assign_seat`.
+                context.suspend();
+                context.resume()?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// A [`LibinputInterface`] opening devices through a [`LibseatSession`].
+///
+/// Opening devices fails with `EBUSY` while the session is inactive.
+pub struct LibseatInterface {
+    inner: Rc<Inner>,
+}
+
+impl fmt::Debug for LibseatInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LibseatInterface")
+            .field("devices", &self.inner.devices)
+            .finish()
+    }
+}
+
+impl LibinputInterface for LibseatInterface {
+    fn open_restricted(&mut self, path: &Path, _flags: i32) -> Result<OwnedFd, i32> {
+        if !self.inner.active.get() {
+            return Err(libc::EBUSY);
+        }
+        let path = CString::new(path.as_os_str().as_bytes()).map_err(|_| libc::EINVAL)?;
+        let mut fd = -1;
+        let device_id =
+            unsafe { ffi::libseat_open_device(self.inner.seat.get(), path.as_ptr(), &mut fd) };
+        if device_id < 0 {
+            return Err(IoError::last_os_error().raw_os_error().unwrap_or(libc::EIO));
+        }
+        self.inner.devices.borrow_mut().insert(fd, device_id);
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn close_restricted(&mut self, fd: OwnedFd) {
+        if let Some(device_id) = self.inner.devices.borrow_mut().remove(&fd.as_raw_fd()) {
+            unsafe { ffi::libseat_close_device(self.inner.seat.get(), device_id) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LibseatSession;
+    use crate::Libinput;
+    use std::{
+        env,
+        os::unix::io::AsRawFd,
+        path::Path,
+        process::{Command, Stdio},
+        thread,
+        time::{Duration, Instant},
+    };
+
+    // Runs against a seatd spawned for the test and is skipped if the
+    // `seatd` binary is not available. libseat takes the socket from the
+    // environment, so the session is opened by `open_session` in a child
+    // process instead of changing the environment of the test threads.
+    #[test]
+    fn session_enables_against_local_seatd() {
+        let dir = env::temp_dir().join(format!("input-rs-seatd-{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let socket = dir.join("seatd.sock");
+        let mut seatd = match Command::new("seatd")
+            .arg("-s")
+            .arg(&socket)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(seatd) => seatd,
+            Err(_) => {
+                eprintln!("seatd not available, skipping");
+                return;
+            }
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !Path::new(&socket).exists() {
+            if Instant::now() > deadline || seatd.try_wait().ok().flatten().is_some() {
+                let _ = seatd.kill();
+                eprintln!("seatd failed to start, skipping");
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let status = Command::new(env::current_exe().unwrap())
+            .args([
+                "--exact",
+                "interface::libseat::tests::open_session",
+                "--ignored",
+                "--nocapture",
+            ])
+            .env("LIBSEAT_BACKEND", "seatd")
+            .env("SEATD_SOCK", &socket)
+            .status();
+
+        let _ = seatd.kill();
+        let _ = seatd.wait();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(status.unwrap().success());
+    }
+
+    #[test]
+    #[ignore = "run by `session_enables_against_local_seatd` with seatd set up"]
+    fn open_session() {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let session = LibseatSession::open().unwrap();
+        let mut context = Libinput::new_from_path(session.interface()).unwrap();
+        while !session.is_active() && Instant::now() < deadline {
+            let mut pollfd = libc::pollfd {
+                fd: session.fd().unwrap().as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            unsafe { libc::poll(&mut pollfd, 1, 100) };
+            session.dispatch(&mut context).unwrap();
+        }
+        assert!(session.is_active());
+        assert!(!session.seat_name().is_empty());
+    }
+}
@@ -67,7 +67,7 @@
 //! #       std::process::exit(0);
 //! #   });
 //! #
-//!     let mut input = Libinput::new_with_udev(Interface);
+//!     let mut input = Libinput::new_with_udev(Interface).unwrap();
 //!     input.udev_assign_seat("seat0").unwrap();
 //!     loop {
 //!         input.dispatch().unwrap();
@@ -226,15 +226,53 @@ macro_rules! ffi_func {
     );
 }
 
+// Calls a libinput function that may be missing from the library in use,
+// evaluating to `None` in that case. Without the `dlopen` feature the function
+// is linked and therefore always available.
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+macro_rules! ffi_dyn {
+    ($ffi_fn:ident($($arg:expr),* $(,)?)) => {{
+        #[cfg(feature = "dlopen")]
+        let ret = $crate::ffi::dynamic::$ffi_fn($($arg),*);
+        #[cfg(not(feature = "dlopen"))]
+        let ret = Some($crate::ffi::$ffi_fn($($arg),*));
+        ret
+    }};
+}
+
+mod config_store;
 mod context;
 mod device;
+mod dwt;
+mod error;
 pub mod event;
+mod hotplug;
+mod inhibit;
+pub mod interface;
+mod overrides;
+mod presets;
+mod registry;
 mod seat;
+mod switch_policy;
 
+pub use config_store::*;
 pub use context::*;
 pub use device::*;
+pub use dwt::*;
+pub use error::*;
 pub use event::Event;
+pub use hotplug::*;
+pub use inhibit::*;
+pub use overrides::*;
+pub use presets::*;
+pub use registry::*;
 pub use seat::*;
+pub use switch_policy::*;
+
+#[cfg(feature = "rules")]
+mod rules;
+#[cfg(feature = "rules")]
+pub use rules::*;
 
 #[cfg(feature = "libinput_1_23")]
 mod accel_config;
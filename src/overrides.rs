@@ -0,0 +1,317 @@
+use crate::{
+    config_store, Device, DeviceConfigError, DeviceConfigResult, DeviceKey, DeviceSetting, Libinput,
+};
+use std::{collections::HashMap, mem};
+
+/// Overrides active on a single device.
+#[derive(Debug, Default)]
+struct DeviceOverrides {
+    /// Values of the overridden settings before the first override
+    base: Vec<DeviceSetting>,
+    /// Active overrides by id, oldest first
+    layers: Vec<(u64, Vec<DeviceSetting>)>,
+}
+
+impl DeviceOverrides {
+    /// The settings of the newest override covering each setting.
+    fn effective(&self) -> Vec<DeviceSetting> {
+        let mut settings = Vec::<DeviceSetting>::new();
+        for (_, layer) in self.layers.iter().rev() {
+            for setting in layer {
+                if !settings.iter().any(|other| other.same_kind(setting)) {
+                    settings.push(*setting);
+                }
+            }
+        }
+        settings
+    }
+}
+
+/// Configuration overrides active on the devices of a context.
+#[derive(Debug, Default)]
+pub(crate) struct OverrideState {
+    next_id: u64,
+    // Keyed like the `ConfigStore`, so overrides survive replugging
+    devices: HashMap<DeviceKey, DeviceOverrides>,
+    // Set while applying overrides, which must not become their own base
+    applying: bool,
+}
+
+fn apply_all(
+    device: &Device,
+    settings: &[DeviceSetting],
+) -> Vec<(DeviceSetting, DeviceConfigResult)> {
+    let context = device.context();
+    let state = context.override_state();
+    let applying = state.map(|state| mem::replace(&mut state.borrow_mut().applying, true));
+    // Overrides are temporary and must not end up in the `ConfigStore`
+    let results = config_store::without_remembering(&context, || {
+        settings
+            .iter()
+            .map(|setting| {
+                let result = match (setting, setting.current(device)) {
+                    // Owned by the inhibitors, see `Device::inhibit`
+                    (DeviceSetting::SendEventsMode(_), _) | (_, None) => {
+                        Err(DeviceConfigError::Unsupported)
+                    }
+                    _ => setting.apply(device),
+                };
+                (*setting, result)
+            })
+            .collect()
+    });
+    if let (Some(state), Some(applying)) = (state, applying) {
+        state.borrow_mut().applying = applying;
+    }
+    results
+}
+
+fn restore(device: &Device, settings: &[DeviceSetting]) {
+    for (_setting, result) in apply_all(device, settings) {
+        if let Err(_err) = result {
+            #[cfg(feature = "log")]
+            log::warn!(
+                "Failed to restore {:?} on {}: {}",
+                _setting,
+                device.sysname().unwrap_or_default(),
+                _err
+            );
+        }
+    }
+}
+
+/// Make a setting applied through a setter the value restored once the
+/// overrides covering it are dropped.
+pub(crate) fn setting_changed(device: &Device, setting: DeviceSetting) {
+    let context = device.context();
+    let state = match context.override_state() {
+        Some(state) => state,
+        None => return,
+    };
+    let mut state = state.borrow_mut();
+    if state.applying || state.devices.is_empty() {
+        return;
+    }
+    let key = config_store::device_key(&context, device);
+    if let Some(base) = state.devices.get_mut(&key).and_then(|overrides| {
+        overrides
+            .base
+            .iter_mut()
+            .find(|base| base.same_kind(&setting))
+    }) {
+        *base = setting;
+    }
+}
+
+/// Re-apply the overrides active for the key of a newly added device.
+pub(crate) fn device_added(context: &Libinput, device: &Device) {
+    let state = match context.override_state() {
+        Some(state) => state,
+        None => return,
+    };
+    let settings = {
+        let state = state.borrow();
+        if state.devices.is_empty() {
+            return;
+        }
+        match state
+            .devices
+            .get(&config_store::device_key(context, device))
+        {
+            Some(overrides) => overrides.effective(),
+            None => return,
+        }
+    };
+    restore(device, &settings);
+}
+
+/// Guard of a configuration override pushed with
+/// [`Device::push_config_override`].
+///
+/// Dropping the guard removes the override. Every setting it covered is
+/// restored to the value of the most recent override still covering it,
+/// or to its value before the first override if there is none. Overrides
+/// may thus be dropped in any order.
+#[must_use = "Dropping the guard immediately restores the overridden settings"]
+#[derive(Debug)]
+pub struct ConfigOverride {
+    device: Device,
+    // `None` for contexts without shared state
+    key: Option<DeviceKey>,
+    id: u64,
+    results: Vec<(DeviceSetting, DeviceConfigResult)>,
+    // Values to restore for contexts without shared state
+    restore: Vec<DeviceSetting>,
+}
+
+impl ConfigOverride {
+    /// The device the override was pushed on.
+    ///
+    /// The device may have been removed since, the override then applies
+    /// to devices with the same [`DeviceKey`] instead.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The result of applying every setting of the override, in order.
+    ///
+    /// Settings that failed to apply are not restored.
+    pub fn results(&self) -> &[(DeviceSetting, DeviceConfigResult)] {
+        &self.results
+    }
+
+    /// Remove the override, equivalent to dropping the guard.
+    pub fn restore(self) {}
+}
+
+impl Drop for ConfigOverride {
+    fn drop(&mut self) {
+        let context = self.device.context();
+        let (state, key) = match (context.override_state(), &self.key) {
+            (Some(state), Some(key)) => (state, key),
+            _ => {
+                restore(&self.device, &self.restore);
+                return;
+            }
+        };
+        let restore_settings = {
+            let mut state = state.borrow_mut();
+            let overrides = match state.devices.get_mut(key) {
+                Some(overrides) => overrides,
+                None => return,
+            };
+            let index = match overrides.layers.iter().position(|(id, _)| *id == self.id) {
+                Some(index) => index,
+                None => return,
+            };
+            let (_, removed) = overrides.layers.remove(index);
+            let mut restore_settings = Vec::new();
+            for setting in removed {
+                // Newer overrides of the setting stay in effect
+                if overrides.layers[index..]
+                    .iter()
+                    .any(|(_, layer)| layer.iter().any(|other| other.same_kind(&setting)))
+                {
+                    continue;
+                }
+                let older = overrides.layers[..index]
+                    .iter()
+                    .rev()
+                    .find_map(|(_, layer)| {
+                        layer
+                            .iter()
+                            .find(|other| other.same_kind(&setting))
+                            .copied()
+                    });
+                match older {
+                    Some(older) => restore_settings.push(older),
+                    None => {
+                        if let Some(pos) = overrides
+                            .base
+                            .iter()
+                            .position(|base| base.same_kind(&setting))
+                        {
+                            restore_settings.push(overrides.base.remove(pos));
+                        }
+                    }
+                }
+            }
+            if overrides.layers.is_empty() {
+                state.devices.remove(key);
+            }
+            restore_settings
+        };
+        let registry = context.device_registry();
+        let devices = match registry.seen(key) {
+            // The device of the guard may have been replugged since
+            Some(_) => registry.by_key(key),
+            None => vec![self.device.clone()],
+        };
+        for device in &devices {
+            restore(device, &restore_settings);
+        }
+    }
+}
+
+impl Device {
+    /// Temporarily override configuration settings of this device.
+    ///
+    /// The settings are applied immediately and restored once the returned
+    /// guard is dropped, e.g. to disable tapping while a game is focused.
+    /// Settings the device does not support fail with
+    /// `DeviceConfigError::Unsupported` and, like other settings failing
+    /// to apply, are not part of the override. The same goes for
+    /// `DeviceSetting::SendEventsMode`, use [`Device::inhibit`] to disable
+    /// a device temporarily instead.
+    ///
+    /// Overrides stack: later overrides take precedence over earlier ones
+    /// covering the same setting, and the settings of a dropped override
+    /// fall back to the overrides still active. Overridden values are not
+    /// remembered by the [`ConfigStore`](crate::ConfigStore).
+    ///
+    /// Overrides are tracked by the [`DeviceKey`] of the device and applied
+    /// again when a device with the same key is added, e.g. after being
+    /// replugged or on [`Libinput::resume`]. Settings changed through the
+    /// `Device::config_*` setters while overridden take effect
+    /// immediately and become the value restored once the last override
+    /// covering them is dropped.
+    ///
+    /// ```no_run
+    /// # use input::{AccelProfile, Device, DeviceSetting};
+    /// # fn focus_game(touchpad: &Device) {
+    /// let guard = touchpad.push_config_override(&[
+    ///     DeviceSetting::Tap(false),
+    ///     DeviceSetting::AccelProfile(AccelProfile::Flat),
+    /// ]);
+    /// // ... once the game loses focus
+    /// drop(guard);
+    /// # }
+    /// ```
+    pub fn push_config_override(&self, settings: &[DeviceSetting]) -> ConfigOverride {
+        // Capture the values before applying anything
+        let current = settings
+            .iter()
+            .filter_map(|setting| setting.current(self))
+            .collect::<Vec<_>>();
+        let results = apply_all(self, settings);
+        let applied = results
+            .iter()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(setting, _)| *setting)
+            .collect::<Vec<_>>();
+
+        let context = self.context();
+        let mut guard = ConfigOverride {
+            device: self.clone(),
+            key: None,
+            id: 0,
+            results,
+            restore: Vec::new(),
+        };
+        match context.override_state() {
+            Some(state) => {
+                let key = config_store::device_key(&context, self);
+                let mut state = state.borrow_mut();
+                guard.id = state.next_id;
+                state.next_id += 1;
+                let overrides = state.devices.entry(key.clone()).or_default();
+                for setting in &applied {
+                    if !overrides.base.iter().any(|base| base.same_kind(setting)) {
+                        if let Some(value) = current.iter().find(|value| value.same_kind(setting)) {
+                            overrides.base.push(*value);
+                        }
+                    }
+                }
+                overrides.layers.push((guard.id, applied));
+                guard.key = Some(key);
+            }
+            None => {
+                guard.restore = current
+                    .into_iter()
+                    .filter(|value| applied.iter().any(|setting| setting.same_kind(value)))
+                    .collect();
+            }
+        }
+        guard
+    }
+}
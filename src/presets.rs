@@ -0,0 +1,243 @@
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+use crate::ThreeFingerDragState;
+use crate::{
+    AccelProfile, ClickMethod, Device, DeviceConfigError, DeviceConfigResult, DeviceSetting,
+    DragLockState, ScrollMethod, TapButtonMap,
+};
+use std::borrow::Cow;
+
+/// `BTN_SIDE` from `linux/input-event-codes.h`
+const BTN_SIDE: u32 = 0x113;
+
+/// A named set of device settings, e.g. a "Mac-like touchpad".
+///
+/// Presets may be applied to any device, settings the device does not
+/// support are skipped and listed by the returned [`PresetReport`].
+///
+/// ```no_run
+/// # use input::{Device, Preset};
+/// # fn configure(device: &Device) {
+/// let preset = Preset::find("mac-touchpad").unwrap();
+/// for diff in preset.diff(device) {
+///     println!("{:?} is {:?}", diff.preset, diff.current);
+/// }
+/// let report = preset.apply(device);
+/// println!("Unsupported: {:?}", report.unsupported());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    name: Cow<'static, str>,
+    settings: Vec<DeviceSetting>,
+}
+
+impl Preset {
+    /// Create a preset from its name and settings.
+    ///
+    /// Settings are applied in order.
+    pub fn new<N: Into<Cow<'static, str>>>(name: N, settings: Vec<DeviceSetting>) -> Preset {
+        Preset {
+            name: name.into(),
+            settings,
+        }
+    }
+
+    /// A touchpad behaving like on macOS: tap-to-click, clickfinger,
+    /// two-finger natural scrolling and, if available, 3-finger dragging.
+    ///
+    /// Named `mac-touchpad`.
+    pub fn mac_touchpad() -> Preset {
+        Preset::new(
+            "mac-touchpad",
+            vec![
+                DeviceSetting::Tap(true),
+                DeviceSetting::TapButtonMap(TapButtonMap::LeftRightMiddle),
+                DeviceSetting::TapDrag(true),
+                DeviceSetting::TapDragLock(DragLockState::Disabled),
+                DeviceSetting::ClickMethod(ClickMethod::Clickfinger),
+                DeviceSetting::ScrollMethod(ScrollMethod::TwoFinger),
+                DeviceSetting::NaturalScroll(true),
+                DeviceSetting::Dwt(true),
+                DeviceSetting::AccelProfile(AccelProfile::Adaptive),
+                #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+                DeviceSetting::ThreeFingerDrag(ThreeFingerDragState::EnabledThreeFinger),
+            ],
+        )
+    }
+
+    /// A touchpad with software button areas, tap-to-click and traditional
+    /// two-finger scrolling.
+    ///
+    /// Named `classic-touchpad`.
+    pub fn classic_touchpad() -> Preset {
+        Preset::new(
+            "classic-touchpad",
+            vec![
+                DeviceSetting::Tap(true),
+                DeviceSetting::TapButtonMap(TapButtonMap::LeftRightMiddle),
+                DeviceSetting::TapDrag(true),
+                DeviceSetting::ClickMethod(ClickMethod::ButtonAreas),
+                DeviceSetting::ScrollMethod(ScrollMethod::TwoFinger),
+                DeviceSetting::NaturalScroll(false),
+                DeviceSetting::Dwt(true),
+                DeviceSetting::AccelProfile(AccelProfile::Adaptive),
+            ],
+        )
+    }
+
+    /// A gaming mouse without pointer acceleration.
+    ///
+    /// Named `gaming-mouse`.
+    pub fn gaming_mouse() -> Preset {
+        Preset::new(
+            "gaming-mouse",
+            vec![
+                DeviceSetting::AccelProfile(AccelProfile::Flat),
+                DeviceSetting::AccelSpeed(0.0),
+                DeviceSetting::MiddleEmulation(false),
+                DeviceSetting::NaturalScroll(false),
+            ],
+        )
+    }
+
+    /// A left-handed trackball scrolling while the side button is held.
+    ///
+    /// Named `left-handed-trackball`.
+    pub fn left_handed_trackball() -> Preset {
+        Preset::new(
+            "left-handed-trackball",
+            vec![
+                DeviceSetting::LeftHanded(true),
+                DeviceSetting::ScrollMethod(ScrollMethod::OnButtonDown),
+                DeviceSetting::ScrollButton(BTN_SIDE),
+                DeviceSetting::AccelProfile(AccelProfile::Adaptive),
+            ],
+        )
+    }
+
+    /// All presets provided by this library.
+    pub fn builtin() -> Vec<Preset> {
+        vec![
+            Preset::mac_touchpad(),
+            Preset::classic_touchpad(),
+            Preset::gaming_mouse(),
+            Preset::left_handed_trackball(),
+        ]
+    }
+
+    /// Look up a preset provided by this library by its name.
+    pub fn find(name: &str) -> Option<Preset> {
+        Preset::builtin()
+            .into_iter()
+            .find(|preset| preset.name == name)
+    }
+
+    /// The name of the preset.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The settings of the preset.
+    pub fn settings(&self) -> &[DeviceSetting] {
+        &self.settings
+    }
+
+    /// Apply the preset to a device.
+    ///
+    /// Settings not supported by the device are not applied and reported
+    /// as `DeviceConfigError::Unsupported`.
+    pub fn apply(&self, device: &Device) -> PresetReport {
+        PresetReport {
+            results: self
+                .settings
+                .iter()
+                .map(|setting| {
+                    let result = match setting.current(device) {
+                        Some(_) => setting.apply(device),
+                        None => Err(DeviceConfigError::Unsupported),
+                    };
+                    (*setting, result)
+                })
+                .collect(),
+        }
+    }
+
+    /// Compare the preset against the current configuration of a device.
+    ///
+    /// Returns the settings of the preset the device is not configured
+    /// with, including the settings it does not support.
+    pub fn diff(&self, device: &Device) -> Vec<SettingDiff> {
+        self.settings
+            .iter()
+            .filter_map(|setting| {
+                let current = setting.current(device);
+                (current != Some(*setting)).then_some(SettingDiff {
+                    preset: *setting,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Outcome of applying a [`Preset`] to a device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetReport {
+    /// The result of every setting of the preset, in order
+    pub results: Vec<(DeviceSetting, DeviceConfigResult)>,
+}
+
+impl PresetReport {
+    /// Settings that were applied.
+    pub fn applied(&self) -> Vec<DeviceSetting> {
+        self.results
+            .iter()
+            .filter(|(_, result)| result.is_ok())
+            .map(|(setting, _)| *setting)
+            .collect()
+    }
+
+    /// Settings the device or the libinput version in use does not
+    /// support.
+    pub fn unsupported(&self) -> Vec<DeviceSetting> {
+        self.results
+            .iter()
+            .filter(|(_, result)| {
+                matches!(
+                    result,
+                    Err(DeviceConfigError::Unsupported | DeviceConfigError::UnsupportedVersion)
+                )
+            })
+            .map(|(setting, _)| *setting)
+            .collect()
+    }
+
+    /// Settings that are supported but failed to apply, e.g. with
+    /// `DeviceConfigError::Invalid`.
+    pub fn failed(&self) -> Vec<(DeviceSetting, DeviceConfigError)> {
+        self.results
+            .iter()
+            .filter_map(|(setting, result)| match result {
+                Err(DeviceConfigError::Unsupported | DeviceConfigError::UnsupportedVersion) => None,
+                Err(err) => Some((*setting, *err)),
+                Ok(()) => None,
+            })
+            .collect()
+    }
+
+    /// Whether every setting of the preset was applied.
+    pub fn is_complete(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+}
+
+/// A setting of a [`Preset`] differing from the configuration of a device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct SettingDiff {
+    /// The setting of the preset
+    pub preset: DeviceSetting,
+    /// The current value of the setting on the device, `None` if the
+    /// device does not support it
+    pub current: Option<DeviceSetting>,
+}
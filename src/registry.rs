@@ -0,0 +1,216 @@
+use crate::{ffi, AsRaw, Device, FromRaw, Libinput, Seat};
+use std::collections::HashMap;
+#[cfg(not(feature = "udev"))]
+use std::fs;
+
+/// Identity of a device that is stable across replugging and restarts.
+///
+/// Unlike [`Device`], which compares by the underlying libinput object, two
+/// keys are equal if they describe the same physical device. Identical
+/// devices plugged into the same port may still share a key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceKey {
+    /// Vendor ID of the device
+    pub vendor: u32,
+    /// Product ID of the device
+    pub product: u32,
+    /// Bus type ID of the device, 0 if unknown
+    pub bustype: u32,
+    /// Descriptive name of the device
+    pub name: String,
+    /// Physical location of the device, e.g. the USB port it is connected to
+    pub phys: Option<String>,
+    /// Unique identifier of the device, e.g. a serial number
+    pub uniq: Option<String>,
+}
+
+/// Read an attribute of the kernel input device `device` belongs to.
+fn input_attribute(device: &Device, name: &str) -> Option<String> {
+    #[cfg(feature = "udev")]
+    let value = device
+        .udev_device()?
+        .parent()?
+        .attribute_value(name)?
+        .to_string_lossy()
+        .into_owned();
+    #[cfg(not(feature = "udev"))]
+    let value = fs::read_to_string(format!(
+        "/sys/class/input/{}/device/{}",
+        device.sysname()?,
+        name
+    ))
+    .ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
+
+impl DeviceKey {
+    /// Build the key of a device.
+    ///
+    /// `phys` and `uniq` are read from the kernel input device, which
+    /// requires the device to be present.
+    pub fn from_device(device: &Device) -> DeviceKey {
+        DeviceKey {
+            vendor: device.id_vendor(),
+            product: device.id_product(),
+            bustype: input_attribute(device, "id/bustype")
+                .and_then(|bustype| u32::from_str_radix(&bustype, 16).ok())
+                .unwrap_or(0),
+            name: device.name().into_owned(),
+            phys: input_attribute(device, "phys"),
+            uniq: input_attribute(device, "uniq"),
+        }
+    }
+}
+
+/// What is known about a device that was added to the context before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeenDevice {
+    /// How often a device with this key was added
+    pub times_added: u32,
+    /// Whether a device with this key is currently present
+    pub present: bool,
+}
+
+/// Devices tracked for a context from its device events.
+#[derive(Debug, Default)]
+pub(crate) struct RegistryState {
+    // Referenced from the added until the removed event was processed. The
+    // devices are not stored as `Device`, which would keep the context alive.
+    devices: Vec<(*mut ffi::libinput_device, DeviceKey)>,
+    seen: HashMap<DeviceKey, SeenDevice>,
+}
+
+impl RegistryState {
+    pub fn add(&mut self, device: &Device) {
+        let key = DeviceKey::from_device(device);
+        let seen = self.seen.entry(key.clone()).or_insert(SeenDevice {
+            times_added: 0,
+            present: false,
+        });
+        seen.times_added += 1;
+        seen.present = true;
+        let ptr = unsafe { ffi::libinput_device_ref(device.as_raw_mut()) };
+        self.devices.push((ptr, key));
+    }
+
+    pub fn remove(&mut self, device: &Device) {
+        let ptr = device.as_raw_mut();
+        if let Some(index) = self.devices.iter().position(|(dev, _)| *dev == ptr) {
+            let (_, key) = self.devices.remove(index);
+            if !self.devices.iter().any(|(_, other)| *other == key) {
+                if let Some(seen) = self.seen.get_mut(&key) {
+                    seen.present = false;
+                }
+            }
+            unsafe { ffi::libinput_device_unref(ptr) };
+        }
+    }
+
+    /// Release all devices, which must happen before the context is
+    /// destroyed.
+    pub fn clear(&mut self) {
+        for (ptr, _) in self.devices.drain(..) {
+            unsafe { ffi::libinput_device_unref(ptr) };
+        }
+    }
+}
+
+/// Enumeration and lookup of the devices of a context.
+///
+/// The registry is maintained by the context from the
+/// [`DeviceAddedEvent`](crate::event::device::DeviceAddedEvent)s and
+/// [`DeviceRemovedEvent`](crate::event::device::DeviceRemovedEvent)s
+/// returned while iterating over it, so devices are listed once their
+/// added event was processed, and until their removed event was processed.
+/// Contexts created with [`Libinput::from_raw`] do not track devices.
+///
+/// ```no_run
+/// use input::{interface::DirectInterface, Libinput};
+///
+/// let mut input = Libinput::new_with_udev(DirectInterface::new()).unwrap();
+/// input.udev_assign_seat("seat0").unwrap();
+/// input.dispatch().unwrap();
+/// for event in &mut input {}
+///
+/// for device in input.device_registry().devices() {
+///     println!("{}: {}", device.sysname().unwrap_or_default(), device.name());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceRegistry {
+    context: Libinput,
+}
+
+impl DeviceRegistry {
+    pub(crate) fn new(context: Libinput) -> DeviceRegistry {
+        DeviceRegistry { context }
+    }
+
+    fn collect<F>(&self, mut filter: F) -> Vec<Device>
+    where
+        F: FnMut(&Device, &DeviceKey) -> bool,
+    {
+        let state = match self.context.registry_state() {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+        let state = state.borrow();
+        state
+            .devices
+            .iter()
+            .map(|(ptr, key)| (unsafe { Device::from_raw(*ptr, &self.context) }, key))
+            .filter(|(device, key)| filter(device, key))
+            .map(|(device, _)| device)
+            .collect()
+    }
+
+    /// All devices currently present, in the order they were added.
+    pub fn devices(&self) -> Vec<Device> {
+        self.collect(|_, _| true)
+    }
+
+    /// All devices currently present on the given seat.
+    pub fn devices_on_seat(&self, seat: &Seat) -> Vec<Device> {
+        self.collect(|device, _| device.seat() == *seat)
+    }
+
+    /// Look up a device by its system name, e.g. `event3`.
+    pub fn by_sysname(&self, sysname: &str) -> Option<Device> {
+        self.collect(|device, _| device.sysname() == Some(sysname))
+            .into_iter()
+            .next()
+    }
+
+    /// All devices currently present with the given key.
+    pub fn by_key(&self, key: &DeviceKey) -> Vec<Device> {
+        self.collect(|_, other| other == key)
+    }
+
+    /// The key a present device was registered with.
+    pub fn key(&self, device: &Device) -> Option<DeviceKey> {
+        let state = self.context.registry_state()?;
+        let state = state.borrow();
+        state
+            .devices
+            .iter()
+            .find(|(ptr, _)| *ptr == device.as_raw_mut())
+            .map(|(_, key)| key.clone())
+    }
+
+    /// What is known about devices with the given key, or `None` if no
+    /// such device was ever added.
+    pub fn seen(&self, key: &DeviceKey) -> Option<SeenDevice> {
+        let state = self.context.registry_state()?;
+        let seen = state.borrow().seen.get(key).copied();
+        seen
+    }
+
+    /// Keys of all devices that were ever added to the context.
+    pub fn seen_keys(&self) -> Vec<DeviceKey> {
+        self.context
+            .registry_state()
+            .map(|state| state.borrow().seen.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
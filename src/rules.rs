@@ -0,0 +1,976 @@
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+use crate::AreaRectangle;
+#[cfg(feature = "libinput_1_26")]
+use crate::ClickfingerButtonMap;
+#[cfg(feature = "libinput_1_15")]
+use crate::ScrollButtonLockState;
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+use crate::ThreeFingerDragState;
+use crate::{
+    config_store::{self, ConfigFailure},
+    hotplug::{inotify_watch, read_inotify},
+    AccelProfile, ClickMethod, Device, DeviceCapability, DeviceKey, DeviceSetting, DragLockState,
+    Libinput, ScrollMethod, SendEventsMode, TapButtonMap,
+};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    error::Error as StdError,
+    ffi::OsString,
+    fmt, fs,
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    mem,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// Errors returned when loading [`DeviceRules`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RulesError {
+    /// The rules file could not be read.
+    Io(IoError),
+    /// The rules are not valid TOML or do not match the expected format.
+    Toml(toml::de::Error),
+    /// The rules are not valid JSON or do not match the expected format.
+    Json(serde_json::Error),
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::Io(err) => write!(f, "Failed to read device rules: {}", err),
+            RulesError::Toml(err) => write!(f, "Invalid device rules: {}", err),
+            RulesError::Json(err) => write!(f, "Invalid device rules: {}", err),
+        }
+    }
+}
+
+impl StdError for RulesError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            RulesError::Io(err) => Some(err),
+            RulesError::Toml(err) => Some(err),
+            RulesError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<IoError> for RulesError {
+    fn from(err: IoError) -> Self {
+        RulesError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for RulesError {
+    fn from(err: toml::de::Error) -> Self {
+        RulesError::Toml(err)
+    }
+}
+
+impl From<serde_json::Error> for RulesError {
+    fn from(err: serde_json::Error) -> Self {
+        RulesError::Json(err)
+    }
+}
+
+/// Match `text` against a shell-style glob supporting `*` and `?`.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum CapabilityName {
+    Keyboard,
+    Pointer,
+    Touch,
+    TabletTool,
+    TabletPad,
+    Gesture,
+    Switch,
+}
+
+impl From<CapabilityName> for DeviceCapability {
+    fn from(cap: CapabilityName) -> Self {
+        match cap {
+            CapabilityName::Keyboard => DeviceCapability::Keyboard,
+            CapabilityName::Pointer => DeviceCapability::Pointer,
+            CapabilityName::Touch => DeviceCapability::Touch,
+            CapabilityName::TabletTool => DeviceCapability::TabletTool,
+            CapabilityName::TabletPad => DeviceCapability::TabletPad,
+            CapabilityName::Gesture => DeviceCapability::Gesture,
+            CapabilityName::Switch => DeviceCapability::Switch,
+        }
+    }
+}
+
+fn capabilities<'de, D>(deserializer: D) -> Result<Vec<DeviceCapability>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let caps = Vec::<CapabilityName>::deserialize(deserializer)?;
+    Ok(caps.into_iter().map(DeviceCapability::from).collect())
+}
+
+/// Conditions a device has to fulfill for a [`Rule`] to apply.
+///
+/// All given conditions have to match. Names are matched as shell-style
+/// globs, where `*` matches any number of characters and `?` matches a
+/// single character.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct DeviceMatch {
+    /// Glob matching the descriptive name of the device
+    pub name: Option<String>,
+    /// Glob matching the system name of the device, e.g. `event*`
+    pub sysname: Option<String>,
+    /// Vendor ID of the device
+    pub vendor: Option<u32>,
+    /// Product ID of the device
+    pub product: Option<u32>,
+    /// Bus type ID of the device
+    pub bustype: Option<u32>,
+    /// Capabilities the device needs to have, e.g. `"pointer"` or
+    /// `"tablet-tool"`
+    #[serde(default, deserialize_with = "capabilities")]
+    pub capabilities: Vec<DeviceCapability>,
+    /// Globs matching udev properties of the device.
+    ///
+    /// Rules with udev properties never match without the `udev` feature.
+    #[serde(default)]
+    pub udev: BTreeMap<String, String>,
+}
+
+impl DeviceMatch {
+    /// Check whether a device fulfills these conditions.
+    pub fn matches(&self, device: &Device) -> bool {
+        self.matches_key(device, &config_store::device_key(&device.context(), device))
+    }
+
+    fn matches_key(&self, device: &Device, key: &DeviceKey) -> bool {
+        self.name
+            .as_ref()
+            .map_or(true, |name| glob_match(name, &key.name))
+            && self.sysname.as_ref().map_or(true, |sysname| {
+                device
+                    .sysname()
+                    .map_or(false, |name| glob_match(sysname, name))
+            })
+            && self.vendor.map_or(true, |vendor| vendor == key.vendor)
+            && self.product.map_or(true, |product| product == key.product)
+            && self.bustype.map_or(true, |bustype| bustype == key.bustype)
+            && self
+                .capabilities
+                .iter()
+                .all(|cap| device.has_capability(*cap))
+            && self
+                .udev
+                .iter()
+                .all(|(property, value)| udev_property_matches(device, property, value))
+    }
+}
+
+#[cfg(feature = "udev")]
+fn udev_property_matches(device: &Device, property: &str, pattern: &str) -> bool {
+    device
+        .udev_device()
+        .and_then(|udev| {
+            udev.property_value(property)
+                .map(|value| glob_match(pattern, &value.to_string_lossy()))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(feature = "udev"))]
+fn udev_property_matches(_device: &Device, _property: &str, _pattern: &str) -> bool {
+    false
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum AccelProfileName {
+    Flat,
+    Adaptive,
+    #[cfg(feature = "libinput_1_23")]
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ClickMethodName {
+    ButtonAreas,
+    Clickfinger,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ScrollMethodName {
+    NoScroll,
+    TwoFinger,
+    Edge,
+    OnButtonDown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ButtonMapName {
+    Lrm,
+    Lmr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum SendEventsName {
+    Enabled,
+    Disabled,
+    DisabledOnExternalMouse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum DragLockName {
+    Disabled,
+    Timeout,
+    #[cfg(feature = "libinput_1_27")]
+    Sticky,
+}
+
+#[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum ThreeFingerDragName {
+    Disabled,
+    ThreeFinger,
+    FourFinger,
+}
+
+#[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Area {
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+}
+
+/// The `config` table of a rule, every entry translates into a
+/// `DeviceSetting`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleConfig {
+    accel_profile: Option<AccelProfileName>,
+    accel_speed: Option<f64>,
+    #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+    area: Option<Area>,
+    calibration_matrix: Option<[f32; 6]>,
+    click_method: Option<ClickMethodName>,
+    #[cfg(feature = "libinput_1_26")]
+    clickfinger_button_map: Option<ButtonMapName>,
+    dwt: Option<bool>,
+    #[cfg(feature = "libinput_1_21")]
+    dwtp: Option<bool>,
+    left_handed: Option<bool>,
+    middle_emulation: Option<bool>,
+    rotation: Option<u32>,
+    scroll_method: Option<ScrollMethodName>,
+    natural_scroll: Option<bool>,
+    scroll_button: Option<u32>,
+    #[cfg(feature = "libinput_1_15")]
+    scroll_button_lock: Option<bool>,
+    send_events: Option<SendEventsName>,
+    #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+    three_finger_drag: Option<ThreeFingerDragName>,
+    tap: Option<bool>,
+    tap_button_map: Option<ButtonMapName>,
+    tap_drag: Option<bool>,
+    tap_drag_lock: Option<DragLockName>,
+}
+
+impl RuleConfig {
+    fn settings(&self) -> Vec<DeviceSetting> {
+        let mut settings = Vec::new();
+        if let Some(profile) = self.accel_profile {
+            settings.push(DeviceSetting::AccelProfile(match profile {
+                AccelProfileName::Flat => AccelProfile::Flat,
+                AccelProfileName::Adaptive => AccelProfile::Adaptive,
+                #[cfg(feature = "libinput_1_23")]
+                AccelProfileName::Custom => AccelProfile::Custom,
+            }));
+        }
+        if let Some(speed) = self.accel_speed {
+            settings.push(DeviceSetting::AccelSpeed(speed));
+        }
+        #[cfg(any(feature = "libinput_1_27", feature = "dlopen"))]
+        if let Some(area) = self.area {
+            settings.push(DeviceSetting::Area(AreaRectangle {
+                x1: area.x1,
+                y1: area.y1,
+                x2: area.x2,
+                y2: area.y2,
+            }));
+        }
+        if let Some(matrix) = self.calibration_matrix {
+            settings.push(DeviceSetting::CalibrationMatrix(matrix));
+        }
+        if let Some(method) = self.click_method {
+            settings.push(DeviceSetting::ClickMethod(match method {
+                ClickMethodName::ButtonAreas => ClickMethod::ButtonAreas,
+                ClickMethodName::Clickfinger => ClickMethod::Clickfinger,
+            }));
+        }
+        #[cfg(feature = "libinput_1_26")]
+        if let Some(map) = self.clickfinger_button_map {
+            settings.push(DeviceSetting::ClickfingerButtonMap(match map {
+                ButtonMapName::Lrm => ClickfingerButtonMap::LeftRightMiddle,
+                ButtonMapName::Lmr => ClickfingerButtonMap::LeftMiddleRight,
+            }));
+        }
+        if let Some(enabled) = self.dwt {
+            settings.push(DeviceSetting::Dwt(enabled));
+        }
+        #[cfg(feature = "libinput_1_21")]
+        if let Some(enabled) = self.dwtp {
+            settings.push(DeviceSetting::Dwtp(enabled));
+        }
+        if let Some(enabled) = self.left_handed {
+            settings.push(DeviceSetting::LeftHanded(enabled));
+        }
+        if let Some(enabled) = self.middle_emulation {
+            settings.push(DeviceSetting::MiddleEmulation(enabled));
+        }
+        if let Some(angle) = self.rotation {
+            settings.push(DeviceSetting::RotationAngle(angle));
+        }
+        if let Some(method) = self.scroll_method {
+            settings.push(DeviceSetting::ScrollMethod(match method {
+                ScrollMethodName::NoScroll => ScrollMethod::NoScroll,
+                ScrollMethodName::TwoFinger => ScrollMethod::TwoFinger,
+                ScrollMethodName::Edge => ScrollMethod::Edge,
+                ScrollMethodName::OnButtonDown => ScrollMethod::OnButtonDown,
+            }));
+        }
+        if let Some(enabled) = self.natural_scroll {
+            settings.push(DeviceSetting::NaturalScroll(enabled));
+        }
+        if let Some(button) = self.scroll_button {
+            settings.push(DeviceSetting::ScrollButton(button));
+        }
+        #[cfg(feature = "libinput_1_15")]
+        if let Some(enabled) = self.scroll_button_lock {
+            settings.push(DeviceSetting::ScrollButtonLock(if enabled {
+                ScrollButtonLockState::Enabled
+            } else {
+                ScrollButtonLockState::Disabled
+            }));
+        }
+        if let Some(mode) = self.send_events {
+            settings.push(DeviceSetting::SendEventsMode(match mode {
+                SendEventsName::Enabled => SendEventsMode::ENABLED,
+                SendEventsName::Disabled => SendEventsMode::DISABLED,
+                SendEventsName::DisabledOnExternalMouse => {
+                    SendEventsMode::DISABLED_ON_EXTERNAL_MOUSE
+                }
+            }));
+        }
+        #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+        if let Some(state) = self.three_finger_drag {
+            settings.push(DeviceSetting::ThreeFingerDrag(match state {
+                ThreeFingerDragName::Disabled => ThreeFingerDragState::Disabled,
+                ThreeFingerDragName::ThreeFinger => ThreeFingerDragState::EnabledThreeFinger,
+                ThreeFingerDragName::FourFinger => ThreeFingerDragState::EnabledFourFinger,
+            }));
+        }
+        if let Some(enabled) = self.tap {
+            settings.push(DeviceSetting::Tap(enabled));
+        }
+        if let Some(map) = self.tap_button_map {
+            settings.push(DeviceSetting::TapButtonMap(match map {
+                ButtonMapName::Lrm => TapButtonMap::LeftRightMiddle,
+                ButtonMapName::Lmr => TapButtonMap::LeftMiddleRight,
+            }));
+        }
+        if let Some(enabled) = self.tap_drag {
+            settings.push(DeviceSetting::TapDrag(enabled));
+        }
+        if let Some(state) = self.tap_drag_lock {
+            settings.push(DeviceSetting::TapDragLock(match state {
+                DragLockName::Disabled => DragLockState::Disabled,
+                DragLockName::Timeout => DragLockState::EnabledTimeout,
+                #[cfg(feature = "libinput_1_27")]
+                DragLockName::Sticky => DragLockState::EnabledSticky,
+            }));
+        }
+        settings
+    }
+}
+
+fn rule_config<'de, D>(deserializer: D) -> Result<Vec<DeviceSetting>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(RuleConfig::deserialize(deserializer)?.settings())
+}
+
+/// A rule assigning configuration to matching devices.
+///
+/// In a rules file, the settings are given by a `config` table. Every entry
+/// is optional and named after the setter of the setting: `accel_profile`,
+/// `accel_speed`, `area`, `calibration_matrix`, `click_method`,
+/// `clickfinger_button_map`, `dwt`, `dwtp`, `left_handed`,
+/// `middle_emulation`, `rotation`, `scroll_method`, `natural_scroll`,
+/// `scroll_button`, `scroll_button_lock`, `send_events`,
+/// `three_finger_drag`, `tap`, `tap_button_map`, `tap_drag` and
+/// `tap_drag_lock`. Enumerations are written in kebab-case, e.g.
+/// `scroll_method = "two-finger"`, `tap_button_map = "lmr"`,
+/// `tap_drag_lock = "timeout"` or
+/// `send_events = "disabled-on-external-mouse"`. Entries for settings not
+/// supported by the enabled libinput version are rejected.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct Rule {
+    /// Conditions for the rule to apply, an empty match applies to all
+    /// devices
+    #[serde(rename = "match", default)]
+    pub matches: DeviceMatch,
+    /// Settings assigned to matching devices
+    #[serde(rename = "config", default, deserialize_with = "rule_config")]
+    pub settings: Vec<DeviceSetting>,
+}
+
+impl Rule {
+    /// Create a rule assigning `settings` to devices matching `matches`.
+    pub fn new(matches: DeviceMatch, settings: Vec<DeviceSetting>) -> Rule {
+        Rule { matches, settings }
+    }
+}
+
+/// Rule-based device configuration loaded from a TOML or JSON file.
+///
+/// Rules are evaluated in order, settings of later matching rules override
+/// the same settings of earlier ones. Once set on a context with
+/// [`Libinput::set_device_rules`], the rules are applied to every device
+/// present and to every device added afterwards while processing its
+/// [`DeviceAddedEvent`](crate::event::device::DeviceAddedEvent).
+///
+/// ```toml
+/// [[rules]]
+/// match = { capabilities = ["pointer"] }
+/// config = { accel_profile = "flat" }
+///
+/// [[rules]]
+/// match = { name = "*Touchpad*", bustype = 0x18 }
+/// config = { tap = true, natural_scroll = true, scroll_method = "two-finger" }
+///
+/// [[rules]]
+/// match.udev = { ID_INPUT_TABLET = "1" }
+/// config.area = { x1 = 0.0, y1 = 0.0, x2 = 1.0, y2 = 0.5625 }
+/// ```
+///
+/// The same structure is used for JSON, e.g.
+/// `{"rules": [{"match": {"vendor": 1133}, "config": {"accel_speed": 0.5}}]}`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceRules {
+    #[serde(default)]
+    rules: Vec<Rule>,
+}
+
+impl DeviceRules {
+    /// Create a rule set from a list of rules.
+    pub fn new(rules: Vec<Rule>) -> DeviceRules {
+        DeviceRules { rules }
+    }
+
+    /// Parse rules in TOML format.
+    pub fn from_toml(rules: &str) -> Result<DeviceRules, RulesError> {
+        Ok(toml::from_str(rules)?)
+    }
+
+    /// Parse rules in JSON format.
+    pub fn from_json(rules: &str) -> Result<DeviceRules, RulesError> {
+        Ok(serde_json::from_str(rules)?)
+    }
+
+    /// Load rules from a file, which is parsed as JSON if its extension is
+    /// `json` and as TOML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<DeviceRules, RulesError> {
+        let path = path.as_ref();
+        let rules = fs::read_to_string(path)?;
+        if path.extension().map_or(false, |ext| ext == "json") {
+            DeviceRules::from_json(&rules)
+        } else {
+            DeviceRules::from_toml(&rules)
+        }
+    }
+
+    /// The rules of this set.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn settings_for_key(&self, device: &Device, key: &DeviceKey) -> Vec<DeviceSetting> {
+        let mut settings: Vec<DeviceSetting> = Vec::new();
+        for rule in self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches.matches_key(device, key))
+        {
+            for setting in &rule.settings {
+                match settings.iter_mut().find(|other| other.same_kind(setting)) {
+                    Some(other) => *other = *setting,
+                    None => settings.push(*setting),
+                }
+            }
+        }
+        settings
+    }
+
+    /// The settings the rules assign to a device.
+    pub fn settings(&self, device: &Device) -> Vec<DeviceSetting> {
+        self.settings_for_key(device, &config_store::device_key(&device.context(), device))
+    }
+}
+
+/// The settings to apply to a device when rules assigning it `previous` are
+/// replaced by rules assigning it `assigned`.
+///
+/// Settings no longer assigned are reset to their `default`, unless the
+/// config store `remembered` a value for them, which is re-applied after
+/// the rules and thus takes precedence over them.
+fn reload_settings<F>(
+    previous: &[DeviceSetting],
+    assigned: &[DeviceSetting],
+    remembered: &[DeviceSetting],
+    default: F,
+) -> Vec<DeviceSetting>
+where
+    F: Fn(&DeviceSetting) -> Option<DeviceSetting>,
+{
+    let covered = |settings: &[DeviceSetting], setting: &DeviceSetting| {
+        settings.iter().any(|other| other.same_kind(setting))
+    };
+    previous
+        .iter()
+        .filter(|setting| !covered(assigned, setting) && !covered(remembered, setting))
+        .filter_map(default)
+        .chain(
+            assigned
+                .iter()
+                .filter(|setting| !covered(remembered, setting))
+                .copied(),
+        )
+        .collect()
+}
+
+/// Re-configure `device` after the rules set on its context replaced
+/// `previous`, in the order of a newly added device: the rules first, the
+/// settings stored for it afterwards and the configuration overrides and
+/// inhibitors of its key last.
+fn reload_rules(
+    context: &Libinput,
+    previous: Option<&DeviceRules>,
+    device: &Device,
+) -> Vec<ConfigFailure> {
+    let state = match context.config_state() {
+        Some(state) => state,
+        None => return Vec::new(),
+    };
+    let key = config_store::device_key(context, device);
+    let settings = {
+        let state = state.borrow();
+        let for_key = |rules: Option<&DeviceRules>| {
+            rules.map_or_else(Vec::new, |rules| rules.settings_for_key(device, &key))
+        };
+        reload_settings(
+            &for_key(previous),
+            &for_key(state.rules.as_deref()),
+            state.remembered(&key),
+            |setting| setting.device_default(device),
+        )
+    };
+    let mut failures = config_store::apply_settings(state, device, &key, settings, false);
+    failures.extend(config_store::reapply(context, device));
+    config_store::apply_temporary(context, device);
+    failures
+}
+
+/// Apply the rules set on the context of `device` to it.
+pub(crate) fn apply_rules(context: &Libinput, device: &Device) -> Vec<ConfigFailure> {
+    let state = match context.config_state() {
+        Some(state) => state,
+        None => return Vec::new(),
+    };
+    let rules = match state.borrow().rules.clone() {
+        Some(rules) => rules,
+        None => return Vec::new(),
+    };
+    let key = config_store::device_key(context, device);
+    let settings = rules.settings_for_key(device, &key);
+    // Settings assigned by rules follow the rules, not the config store
+    config_store::apply_settings(state, device, &key, settings, false)
+}
+
+impl Libinput {
+    /// Set the rules configuring devices of this context, or remove them.
+    ///
+    /// The rules are applied to all devices currently present, returning
+    /// the settings that failed to apply. Devices added afterwards are
+    /// configured while processing their `DeviceAddedEvent`, failures are
+    /// recorded in the [`ConfigStore`](crate::ConfigStore).
+    ///
+    /// Settings assigned by the rules are not remembered by the
+    /// `ConfigStore`, while settings it remembers are re-applied after the
+    /// rules. Settings assigned by the previous rules but not by the new
+    /// ones are reset to the value remembered by the `ConfigStore`, or to
+    /// the default of the device. Active configuration overrides and
+    /// inhibitors are re-applied last, and the rules do not become the
+    /// value restored once they end.
+    pub fn set_device_rules(&self, rules: Option<DeviceRules>) -> Vec<ConfigFailure> {
+        let state = match self.config_state() {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+        let previous = mem::replace(&mut state.borrow_mut().rules, rules.map(Rc::new));
+        self.device_registry()
+            .devices()
+            .iter()
+            .flat_map(|device| reload_rules(self, previous.as_deref(), device))
+            .collect()
+    }
+
+    /// The rules configuring devices of this context.
+    pub fn device_rules(&self) -> Option<DeviceRules> {
+        self.config_state()
+            .and_then(|state| state.borrow().rules.as_deref().cloned())
+    }
+}
+
+/// Hot-reloading of a [`DeviceRules`] file.
+///
+/// Watches the file with inotify and sets the reloaded rules on a context
+/// whenever the file was written or replaced. The watcher exposes a file
+/// descriptor, which becomes readable once [`RulesWatcher::dispatch`] needs
+/// to be called.
+///
+/// ```no_run
+/// use input::{interface::DirectInterface, Libinput, RulesWatcher};
+///
+/// let mut input = Libinput::new_with_udev(DirectInterface::new()).unwrap();
+/// let mut watcher = RulesWatcher::new("/etc/my-compositor/input.toml").unwrap();
+/// watcher.load(&input).unwrap();
+/// input.udev_assign_seat("seat0").unwrap();
+/// loop {
+///     // Wait for `watcher` or `input` to become readable
+///     if let Err(err) = watcher.dispatch(&input) {
+///         eprintln!("Keeping previous rules: {}", err);
+///     }
+///     input.dispatch().unwrap();
+///     for event in &mut input {}
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RulesWatcher {
+    inotify: OwnedFd,
+    path: PathBuf,
+    file_name: OsString,
+}
+
+impl RulesWatcher {
+    /// Watch the rules file at `path`.
+    ///
+    /// The directory of the file is watched, so the file may not exist yet
+    /// and may be replaced by editors.
+    pub fn new<P: AsRef<Path>>(path: P) -> IoResult<RulesWatcher> {
+        let path = path.as_ref().to_path_buf();
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| IoError::new(ErrorKind::InvalidInput, "Not a file path"))?
+            .to_os_string();
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let inotify = inotify_watch(
+            directory,
+            libc::IN_CLOSE_WRITE | libc::IN_MOVED_TO | libc::IN_CREATE,
+        )?;
+        Ok(RulesWatcher {
+            inotify,
+            path,
+            file_name,
+        })
+    }
+
+    /// The watched path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the rules file and set its rules on `context`.
+    ///
+    /// Returns the settings that failed to apply to present devices. If the
+    /// file cannot be loaded, the rules of `context` are left untouched.
+    pub fn load(&self, context: &Libinput) -> Result<Vec<ConfigFailure>, RulesError> {
+        let rules = DeviceRules::load(&self.path)?;
+        Ok(context.set_device_rules(Some(rules)))
+    }
+
+    /// Process pending inotify events without blocking, reloading the rules
+    /// if the file changed.
+    ///
+    /// Returns the settings that failed to apply if the rules were
+    /// reloaded, or `None` if the file did not change.
+    pub fn dispatch(
+        &mut self,
+        context: &Libinput,
+    ) -> Result<Option<Vec<ConfigFailure>>, RulesError> {
+        let mut changed = false;
+        read_inotify(self.inotify.as_fd(), |_, name| {
+            changed |= name == self.file_name;
+        })?;
+        if changed {
+            self.load(context).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl AsRawFd for RulesWatcher {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inotify.as_raw_fd()
+    }
+}
+
+impl AsFd for RulesWatcher {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inotify.as_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, reload_settings, DeviceMatch, DeviceRules, Rule, RulesError};
+    use crate::{AccelProfile, DeviceCapability, DeviceSetting, ScrollMethod};
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("", ""));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("event3", "event3"));
+        assert!(!glob_match("event3", "event30"));
+        assert!(glob_match("event?", "event3"));
+        assert!(!glob_match("event?", "event"));
+        assert!(glob_match("event*", "event12"));
+        assert!(glob_match("*Touchpad*", "SYNA Touchpad"));
+        assert!(!glob_match("*Touchpad*", "SYNA TouchPad"));
+        assert!(glob_match("a*c", "abcbc"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxbyy"));
+        assert!(glob_match("*?", "x"));
+        assert!(!glob_match("*?", ""));
+    }
+
+    fn example() -> DeviceRules {
+        DeviceRules::new(vec![
+            Rule::new(
+                DeviceMatch {
+                    capabilities: vec![DeviceCapability::Pointer],
+                    ..DeviceMatch::default()
+                },
+                vec![DeviceSetting::AccelProfile(AccelProfile::Flat)],
+            ),
+            Rule::new(
+                DeviceMatch {
+                    name: Some("*Touchpad*".to_owned()),
+                    bustype: Some(0x18),
+                    ..DeviceMatch::default()
+                },
+                vec![
+                    DeviceSetting::ScrollMethod(ScrollMethod::TwoFinger),
+                    DeviceSetting::NaturalScroll(true),
+                    DeviceSetting::Tap(true),
+                ],
+            ),
+            Rule::new(
+                DeviceMatch {
+                    udev: [("ID_INPUT_TABLET".to_owned(), "1".to_owned())]
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    ..DeviceMatch::default()
+                },
+                vec![DeviceSetting::AccelSpeed(0.5)],
+            ),
+        ])
+    }
+
+    #[test]
+    fn reload() {
+        let default = |setting: &DeviceSetting| match setting {
+            DeviceSetting::NaturalScroll(_) => Some(DeviceSetting::NaturalScroll(false)),
+            DeviceSetting::Tap(_) => Some(DeviceSetting::Tap(false)),
+            _ => None,
+        };
+        let previous = [
+            DeviceSetting::NaturalScroll(true),
+            DeviceSetting::Tap(true),
+            DeviceSetting::AccelSpeed(0.5),
+        ];
+        // Dropped settings are reset, assigned ones applied
+        assert_eq!(
+            reload_settings(&previous, &[DeviceSetting::AccelSpeed(0.2)], &[], default),
+            vec![
+                DeviceSetting::NaturalScroll(false),
+                DeviceSetting::Tap(false),
+                DeviceSetting::AccelSpeed(0.2),
+            ]
+        );
+        // A remembered setting survives the reload, whether the new rules
+        // drop or assign it
+        let remembered = [DeviceSetting::Tap(true), DeviceSetting::AccelSpeed(0.8)];
+        assert_eq!(
+            reload_settings(
+                &previous,
+                &[DeviceSetting::AccelSpeed(0.2)],
+                &remembered,
+                default
+            ),
+            vec![DeviceSetting::NaturalScroll(false)]
+        );
+        assert_eq!(
+            reload_settings(&[], &previous, &remembered, default),
+            vec![DeviceSetting::NaturalScroll(true)]
+        );
+    }
+
+    #[test]
+    fn parse_toml() {
+        let rules = DeviceRules::from_toml(
+            r#"
+            [[rules]]
+            match = { capabilities = ["pointer"] }
+            config = { accel_profile = "flat" }
+
+            [[rules]]
+            match = { name = "*Touchpad*", bustype = 0x18 }
+            config = { tap = true, natural_scroll = true, scroll_method = "two-finger" }
+
+            [[rules]]
+            match.udev = { ID_INPUT_TABLET = "1" }
+            config.accel_speed = 0.5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(rules, example());
+    }
+
+    #[test]
+    fn parse_json() {
+        let rules = DeviceRules::from_json(
+            r#"{"rules": [
+                {"match": {"capabilities": ["pointer"]}, "config": {"accel_profile": "flat"}},
+                {
+                    "match": {"name": "*Touchpad*", "bustype": 24},
+                    "config": {"tap": true, "natural_scroll": true, "scroll_method": "two-finger"}
+                },
+                {"match": {"udev": {"ID_INPUT_TABLET": "1"}}, "config": {"accel_speed": 0.5}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(rules, example());
+        assert_eq!(
+            DeviceRules::from_json("{}").unwrap(),
+            DeviceRules::default()
+        );
+    }
+
+    #[test]
+    fn reject_unknown_fields() {
+        for toml in [
+            "unknown = 1",
+            "[[rules]]\nunknown = 1",
+            "[[rules]]\nmatch = { unknown = 1 }",
+            "[[rules]]\nconfig = { unknown = 1 }",
+            "[[rules]]\nconfig = { scroll_method = \"two-fingers\" }",
+            "[[rules]]\nmatch = { capabilities = [\"mouse\"] }",
+        ]
+        .iter()
+        {
+            assert!(
+                matches!(DeviceRules::from_toml(toml), Err(RulesError::Toml(_))),
+                "{}",
+                toml
+            );
+        }
+        assert!(matches!(
+            DeviceRules::from_json(r#"{"rules": [{"config": {"unknown": 1}}]}"#),
+            Err(RulesError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn feature_gated_keys() {
+        let dwtp = DeviceRules::from_toml("[[rules]]\nconfig = { dwtp = true }");
+        #[cfg(feature = "libinput_1_21")]
+        assert_eq!(
+            dwtp.unwrap().rules()[0].settings,
+            vec![DeviceSetting::Dwtp(true)]
+        );
+        #[cfg(not(feature = "libinput_1_21"))]
+        assert!(dwtp.is_err());
+
+        let sticky = DeviceRules::from_toml("[[rules]]\nconfig = { tap_drag_lock = \"sticky\" }");
+        #[cfg(feature = "libinput_1_27")]
+        assert_eq!(
+            sticky.unwrap().rules()[0].settings,
+            vec![DeviceSetting::TapDragLock(
+                crate::DragLockState::EnabledSticky
+            )]
+        );
+        #[cfg(not(feature = "libinput_1_27"))]
+        assert!(sticky.is_err());
+
+        let drag =
+            DeviceRules::from_toml("[[rules]]\nconfig = { three_finger_drag = \"four-finger\" }");
+        #[cfg(any(feature = "libinput_1_28", feature = "dlopen"))]
+        assert_eq!(
+            drag.unwrap().rules()[0].settings,
+            vec![DeviceSetting::ThreeFingerDrag(
+                crate::ThreeFingerDragState::EnabledFourFinger
+            )]
+        );
+        #[cfg(not(any(feature = "libinput_1_28", feature = "dlopen")))]
+        assert!(drag.is_err());
+    }
+}
@@ -0,0 +1,465 @@
+use crate::{
+    event::{
+        switch::{Switch, SwitchState},
+        DeviceEvent, EventTrait, SwitchEvent,
+    },
+    Device, DeviceCapability, DeviceInhibitor, Error, Event, InhibitMode, Libinput,
+};
+#[cfg(feature = "udev")]
+use std::ffi::OsStr;
+use std::{
+    io::Error as IoError,
+    mem,
+    os::unix::io::AsRawFd,
+    path::{Path, PathBuf},
+};
+
+/// `KEY_A` from `linux/input-event-codes.h`
+const KEY_A: u32 = 30;
+/// `SW_LID` from `linux/input-event-codes.h`
+const SW_LID: usize = 0x00;
+/// `SW_TABLET_MODE` from `linux/input-event-codes.h`
+const SW_TABLET_MODE: usize = 0x01;
+/// Size of the switch bitmask read with `EVIOCGSW`, covers `SW_MAX`
+const SW_BYTES: usize = 8;
+
+/// Bus types of devices built into the computer, from `linux/input.h`
+const INTERNAL_BUSTYPES: [u32; 5] = [
+    0x11, // BUS_I8042
+    0x18, // BUS_I2C
+    0x19, // BUS_HOST
+    0x1C, // BUS_SPI
+    0x1D, // BUS_RMI
+];
+
+/// `EVIOCGSW(len)` from `linux/input.h`
+const fn eviocgsw(len: usize) -> u64 {
+    // _IOC(_IOC_READ, 'E', 0x1b, len)
+    (2 << 30) | ((len as u64) << 16) | ((b'E' as u64) << 8) | 0x1b
+}
+
+fn devnode(device: &Device) -> Option<PathBuf> {
+    #[cfg(feature = "udev")]
+    if let Some(path) = device
+        .udev_device()
+        .and_then(|udev| udev.devnode().map(Path::to_path_buf))
+    {
+        return Some(path);
+    }
+    device
+        .sysname()
+        .map(|sysname| Path::new("/dev/input").join(sysname))
+}
+
+/// Kind of device a [`PolicyRule`] applies to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DeviceClass {
+    /// Keyboards with alphanumeric keys, excluding e.g. power buttons
+    Keyboard,
+    /// Touchpads
+    Touchpad,
+    /// Pointer devices other than touchpads, e.g. mice or trackpoints
+    Pointer,
+    /// Touchscreens
+    Touchscreen,
+    /// Graphics tablets
+    Tablet,
+}
+
+impl DeviceClass {
+    /// Whether the device belongs to this class.
+    pub fn matches(self, device: &Device) -> bool {
+        let touchpad = || {
+            device.has_capability(DeviceCapability::Pointer) && device.config_tap_finger_count() > 0
+        };
+        match self {
+            DeviceClass::Keyboard => device.keyboard_has_key(KEY_A).unwrap_or(false),
+            DeviceClass::Touchpad => touchpad(),
+            DeviceClass::Pointer => device.has_capability(DeviceCapability::Pointer) && !touchpad(),
+            DeviceClass::Touchscreen => device.has_capability(DeviceCapability::Touch),
+            DeviceClass::Tablet => {
+                device.has_capability(DeviceCapability::TabletTool)
+                    || device.has_capability(DeviceCapability::TabletPad)
+            }
+        }
+    }
+}
+
+/// A rule of a [`SwitchPolicy`]: inhibit devices of a class while a switch
+/// is on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct PolicyRule {
+    /// The switch triggering the rule
+    pub switch: Switch,
+    /// The devices inhibited while the switch is on
+    pub class: DeviceClass,
+    /// Whether only devices built into the computer are inhibited, see
+    /// [`Device::is_internal`]
+    pub internal_only: bool,
+    /// How the devices are inhibited
+    pub mode: InhibitMode,
+}
+
+impl PolicyRule {
+    /// Create a rule inhibiting internal devices of a class while the
+    /// switch is on.
+    pub fn new(switch: Switch, class: DeviceClass, mode: InhibitMode) -> PolicyRule {
+        PolicyRule {
+            switch,
+            class,
+            internal_only: true,
+            mode,
+        }
+    }
+
+    /// Apply the rule to external devices as well.
+    pub fn include_external(mut self) -> PolicyRule {
+        self.internal_only = false;
+        self
+    }
+
+    fn applies_to(&self, device: &TrackedDevice) -> bool {
+        (device.internal || !self.internal_only)
+            // Inhibiting the device reporting the switch would lose its events
+            && !device.switches.contains(&self.switch)
+            && self.class.matches(&device.device)
+    }
+}
+
+fn inhibitor_name(switch: Switch) -> &'static str {
+    match switch {
+        Switch::Lid => "switch-policy:lid",
+        Switch::TabletMode => "switch-policy:tablet-mode",
+    }
+}
+
+#[derive(Debug)]
+struct TrackedDevice {
+    device: Device,
+    internal: bool,
+    switches: Vec<Switch>,
+    /// Last known state of every switch of the device
+    states: Vec<(Switch, SwitchState)>,
+}
+
+impl TrackedDevice {
+    fn new(device: Device) -> TrackedDevice {
+        let switches = [Switch::Lid, Switch::TabletMode]
+            .iter()
+            .copied()
+            .filter(|switch| device.switch_has_switch(*switch).unwrap_or(false))
+            .collect::<Vec<_>>();
+        let states = switches
+            .iter()
+            .filter_map(|switch| match device.switch_state(*switch) {
+                Ok(state) => Some((*switch, state)),
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Failed to read {:?} switch state of {}: {}",
+                        switch,
+                        device.sysname().unwrap_or_default(),
+                        _err
+                    );
+                    None
+                }
+            })
+            .collect();
+        TrackedDevice {
+            internal: device.is_internal(),
+            device,
+            switches,
+            states,
+        }
+    }
+}
+
+/// Inhibits devices depending on the lid and tablet-mode switches.
+///
+/// The policy tracks the state of every switch device and inhibits the
+/// devices matched by its rules through [`Device::inhibit`] while the
+/// corresponding switch is on. The state of switches that did not toggle
+/// yet is read from the kernel when the switch device is added.
+///
+/// The default rules disable the internal keyboard and touchpad in tablet
+/// mode and the internal touchscreen while the lid is closed.
+///
+/// ```no_run
+/// # use input::{Libinput, SwitchPolicy};
+/// # fn run(mut context: Libinput) {
+/// let mut policy = SwitchPolicy::new(&context);
+/// loop {
+///     context.dispatch().unwrap();
+///     for event in &mut context {
+///         policy.handle_event(&event);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SwitchPolicy {
+    rules: Vec<PolicyRule>,
+    devices: Vec<TrackedDevice>,
+    /// Active inhibitors by rule index
+    inhibitors: Vec<(usize, DeviceInhibitor)>,
+}
+
+impl SwitchPolicy {
+    /// Create a policy with the default rules, tracking the devices
+    /// currently present in the context.
+    pub fn new(context: &Libinput) -> SwitchPolicy {
+        SwitchPolicy::with_rules(context, SwitchPolicy::default_rules())
+    }
+
+    /// Create a policy with the given rules, tracking the devices currently
+    /// present in the context.
+    pub fn with_rules(context: &Libinput, rules: Vec<PolicyRule>) -> SwitchPolicy {
+        let mut policy = SwitchPolicy {
+            rules,
+            devices: context
+                .device_registry()
+                .devices()
+                .into_iter()
+                .map(TrackedDevice::new)
+                .collect(),
+            inhibitors: Vec::new(),
+        };
+        policy.evaluate();
+        policy
+    }
+
+    /// The rules used by `SwitchPolicy::new`.
+    pub fn default_rules() -> Vec<PolicyRule> {
+        vec![
+            PolicyRule::new(
+                Switch::TabletMode,
+                DeviceClass::Keyboard,
+                InhibitMode::Disabled,
+            ),
+            PolicyRule::new(
+                Switch::TabletMode,
+                DeviceClass::Touchpad,
+                InhibitMode::Disabled,
+            ),
+            PolicyRule::new(Switch::Lid, DeviceClass::Touchscreen, InhibitMode::Disabled),
+        ]
+    }
+
+    /// The rules of the policy.
+    pub fn rules(&self) -> &[PolicyRule] {
+        &self.rules
+    }
+
+    /// Replace the rules of the policy and re-evaluate them.
+    ///
+    /// Devices inhibited by a rule that is kept stay inhibited throughout.
+    pub fn set_rules(&mut self, rules: Vec<PolicyRule>) {
+        let previous = mem::replace(&mut self.rules, rules);
+        // Move the inhibitors of kept rules to their new index
+        let mut released = Vec::new();
+        for (index, inhibitor) in mem::take(&mut self.inhibitors) {
+            let index = self.rules.iter().position(|rule| *rule == previous[index]);
+            match index {
+                Some(index)
+                    if !self.inhibitors.iter().any(|(other, kept)| {
+                        *other == index && kept.device() == inhibitor.device()
+                    }) =>
+                {
+                    self.inhibitors.push((index, inhibitor))
+                }
+                _ => released.push(inhibitor),
+            }
+        }
+        self.evaluate();
+        // Released last, so devices also inhibited by the new rules are not
+        // enabled in between
+        drop(released);
+    }
+
+    /// Update the policy from an event of the context.
+    ///
+    /// Device added and removed events and switch toggles are handled,
+    /// all other events are ignored.
+    pub fn handle_event(&mut self, event: &Event) {
+        match event {
+            Event::Device(DeviceEvent::Added(event)) => {
+                let device = event.device();
+                if self.devices.iter().any(|tracked| tracked.device == device) {
+                    return;
+                }
+                self.devices.push(TrackedDevice::new(device));
+            }
+            Event::Device(DeviceEvent::Removed(event)) => {
+                let device = event.device();
+                self.inhibitors
+                    .retain(|(_, inhibitor)| *inhibitor.device() != device);
+                self.devices.retain(|tracked| tracked.device != device);
+            }
+            Event::Switch(SwitchEvent::Toggle(event)) => {
+                let switch = match event.switch() {
+                    Some(switch) => switch,
+                    None => return,
+                };
+                let device = event.device();
+                let tracked = match self
+                    .devices
+                    .iter_mut()
+                    .find(|tracked| tracked.device == device)
+                {
+                    Some(tracked) => tracked,
+                    None => {
+                        self.devices.push(TrackedDevice::new(device));
+                        self.devices.last_mut().unwrap()
+                    }
+                };
+                tracked.states.retain(|(other, _)| *other != switch);
+                tracked.states.push((switch, event.switch_state()));
+            }
+            _ => return,
+        }
+        self.evaluate();
+    }
+
+    /// The state of a switch over all devices providing it.
+    ///
+    /// The switch is on if it is on for any device. Returns `None` if no
+    /// device provides the switch or its state is not known.
+    pub fn switch_state(&self, switch: Switch) -> Option<SwitchState> {
+        let mut states = self
+            .devices
+            .iter()
+            .flat_map(|tracked| tracked.states.iter())
+            .filter(|(other, _)| *other == switch)
+            .map(|(_, state)| *state)
+            .peekable();
+        let first = *states.peek()?;
+        Some(
+            states
+                .find(|state| *state == SwitchState::On)
+                .unwrap_or(first),
+        )
+    }
+
+    /// The last known state of a switch of a device.
+    ///
+    /// Returns `None` if the device is not tracked, does not provide the
+    /// switch or its state is not known.
+    pub fn device_switch_state(&self, device: &Device, switch: Switch) -> Option<SwitchState> {
+        self.devices
+            .iter()
+            .find(|tracked| tracked.device == *device)?
+            .states
+            .iter()
+            .find(|(other, _)| *other == switch)
+            .map(|(_, state)| *state)
+    }
+
+    /// The devices currently inhibited by the policy together with the
+    /// rule inhibiting them.
+    pub fn inhibited_devices(&self) -> Vec<(PolicyRule, Device)> {
+        self.inhibitors
+            .iter()
+            .map(|(rule, inhibitor)| (self.rules[*rule], inhibitor.device().clone()))
+            .collect()
+    }
+
+    fn evaluate(&mut self) {
+        let mut wanted = Vec::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            if self.switch_state(rule.switch) != Some(SwitchState::On) {
+                continue;
+            }
+            for tracked in &self.devices {
+                if rule.applies_to(tracked) {
+                    wanted.push((index, &tracked.device));
+                }
+            }
+        }
+
+        self.inhibitors
+            .retain(|(index, inhibitor)| wanted.contains(&(*index, inhibitor.device())));
+        for (index, device) in wanted {
+            if self
+                .inhibitors
+                .iter()
+                .any(|(other, inhibitor)| *other == index && inhibitor.device() == device)
+            {
+                continue;
+            }
+            let rule = &self.rules[index];
+            match device.inhibit(inhibitor_name(rule.switch), rule.mode) {
+                Ok(inhibitor) => self.inhibitors.push((index, inhibitor)),
+                Err(_err) => {
+                    #[cfg(feature = "log")]
+                    log::warn!(
+                        "Failed to inhibit {} on {:?} switch: {}",
+                        device.sysname().unwrap_or_default(),
+                        rule.switch,
+                        _err
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Device {
+    /// Whether the device is built into the computer, e.g. the keyboard
+    /// and touchpad of a laptop.
+    ///
+    /// The `ID_INTEGRATION` and `ID_INPUT_TOUCHPAD_INTEGRATION` udev
+    /// properties are used if set, otherwise the device is considered
+    /// internal if it is connected through a bus used for built-in
+    /// devices, e.g. i8042 or I²C.
+    pub fn is_internal(&self) -> bool {
+        #[cfg(feature = "udev")]
+        if let Some(udev) = self.udev_device() {
+            for property in &["ID_INPUT_TOUCHPAD_INTEGRATION", "ID_INTEGRATION"] {
+                match udev.property_value(property).and_then(OsStr::to_str) {
+                    Some("internal") => return true,
+                    Some("external") => return false,
+                    _ => {}
+                }
+            }
+        }
+        INTERNAL_BUSTYPES.contains(&crate::DeviceKey::from_device(self).bustype)
+    }
+
+    /// Read the current state of a switch of this device from the kernel.
+    ///
+    /// Unlike `SwitchToggleEvent`, this also works for switches that did
+    /// not toggle since the device was added. The device node is opened
+    /// through the `LibinputInterface` of the context.
+    ///
+    /// Returns `Error::MissingCapability` if the device does not provide
+    /// the switch.
+    pub fn switch_state(&self, switch: Switch) -> Result<SwitchState, Error> {
+        if !self.switch_has_switch(switch)? {
+            return Err(Error::MissingCapability(DeviceCapability::Switch));
+        }
+        let bit = match switch {
+            Switch::Lid => SW_LID,
+            Switch::TabletMode => SW_TABLET_MODE,
+        };
+        let path =
+            devnode(self).ok_or_else(|| Error::Io(IoError::from_raw_os_error(libc::ENODEV)))?;
+        let context = self.context();
+        let fd = context
+            .open_device_node(&path, libc::O_RDONLY | libc::O_NONBLOCK | libc::O_CLOEXEC)
+            .map_err(|errno| Error::Io(IoError::from_raw_os_error(errno.abs())))?;
+        let mut bits = [0u8; SW_BYTES];
+        let res =
+            unsafe { libc::ioctl(fd.as_raw_fd(), eviocgsw(SW_BYTES) as _, bits.as_mut_ptr()) };
+        let result = if res < 0 {
+            Err(Error::Io(IoError::last_os_error()))
+        } else if bits[bit / 8] & (1 << (bit % 8)) != 0 {
+            Ok(SwitchState::On)
+        } else {
+            Ok(SwitchState::Off)
+        };
+        context.close_device_node(fd);
+        result
+    }
+}